@@ -1,6 +1,6 @@
 use crate::TaskInfo;
 
-use super::{Stat, TimeVal};
+use super::{SchedParam, Stat, TaskBrief, TimeSpec, TimeVal, TraceEntry, TrapStats};
 
 pub const SYSCALL_OPENAT: usize = 56;
 pub const SYSCALL_CLOSE: usize = 57;
@@ -9,24 +9,64 @@ pub const SYSCALL_WRITE: usize = 64;
 pub const SYSCALL_UNLINKAT: usize = 35;
 pub const SYSCALL_LINKAT: usize = 37;
 pub const SYSCALL_FSTAT: usize = 80;
+pub const SYSCALL_CLOCK_GETTIME: usize = 113;
 pub const SYSCALL_EXIT: usize = 93;
 pub const SYSCALL_SLEEP: usize = 101;
 pub const SYSCALL_YIELD: usize = 124;
+pub const SYSCALL_KILL: usize = 129;
+pub const SYSCALL_SIGRETURN: usize = 139;
 pub const SYSCALL_GETTIMEOFDAY: usize = 169;
 pub const SYSCALL_GETPID: usize = 172;
 pub const SYSCALL_GETTID: usize = 178;
 pub const SYSCALL_FORK: usize = 220;
 pub const SYSCALL_EXEC: usize = 221;
 pub const SYSCALL_WAITPID: usize = 260;
+pub const SYSCALL_SCHED_SETPARAM: usize = 118;
+pub const SYSCALL_SCHED_GETPARAM: usize = 121;
 pub const SYSCALL_SET_PRIORITY: usize = 140;
+pub const SYSCALL_REBOOT: usize = 142;
+pub const SYSCALL_SBRK: usize = 214;
 pub const SYSCALL_MUNMAP: usize = 215;
 pub const SYSCALL_MMAP: usize = 222;
+pub const SYSCALL_GETRANDOM: usize = 278;
 pub const SYSCALL_SPAWN: usize = 400;
 pub const SYSCALL_MAIL_READ: usize = 401;
 pub const SYSCALL_MAIL_WRITE: usize = 402;
 pub const SYSCALL_DUP: usize = 24;
 pub const SYSCALL_PIPE: usize = 59;
 pub const SYSCALL_TASK_INFO: usize = 410;
+pub const SYSCALL_TASK_INFO_OF: usize = 411;
+pub const SYSCALL_TASK_NAME: usize = 412;
+pub const SYSCALL_GET_CLOCK_FREQ: usize = 413;
+pub const SYSCALL_GET_TICKS: usize = 414;
+pub const SYSCALL_TRAP_STATS: usize = 415;
+pub const SYSCALL_SET_DEADLINE: usize = 416;
+pub const SYSCALL_SET_CPU_LIMIT: usize = 417;
+pub const SYSCALL_YIELD_TO: usize = 418;
+pub const SYSCALL_SET_TICKETS: usize = 419;
+pub const SYSCALL_LIST_TASKS: usize = 420;
+pub const SYSCALL_IDLE_TIME: usize = 421;
+pub const SYSCALL_NANOSLEEP: usize = 422;
+pub const SYSCALL_SET_ALARM: usize = 423;
+pub const SYSCALL_POLL_ALARM: usize = 424;
+pub const SYSCALL_SET_ALARM_HANDLER: usize = 425;
+pub const SYSCALL_UPTIME: usize = 426;
+pub const SYSCALL_SET_AFFINITY: usize = 427;
+pub const SYSCALL_WAIT_EVENT: usize = 428;
+pub const SYSCALL_SIGNAL_EVENT: usize = 429;
+pub const SYSCALL_SEND: usize = 430;
+pub const SYSCALL_RECV: usize = 431;
+pub const SYSCALL_GET_TASK_COUNT: usize = 432;
+pub const SYSCALL_STACK_USAGE: usize = 433;
+pub const SYSCALL_DUMP_REGS: usize = 434;
+pub const SYSCALL_KERNEL_FAULT: usize = 435;
+pub const SYSCALL_SYSCALL_NAME: usize = 436;
+pub const SYSCALL_KERNEL_ASSERT: usize = 437;
+pub const SYSCALL_DUMP_TRACE: usize = 438;
+pub const SYSCALL_CLOBBER_STACK_GUARD: usize = 439;
+pub const SYSCALL_CLOBBER_CONTEXT_CANARY: usize = 440;
+pub const SYSCALL_KERNEL_PANIC: usize = 441;
+pub const SYSCALL_KERNEL_PANIC_LOCKED: usize = 442;
 pub const SYSCALL_THREAD_CREATE: usize = 460;
 pub const SYSCALL_WAITTID: usize = 462;
 pub const SYSCALL_MUTEX_CREATE: usize = 463;
@@ -150,6 +190,11 @@ pub fn sys_sleep(sleep_ms: usize) -> isize {
     syscall(SYSCALL_SLEEP, [sleep_ms, 0, 0])
 }
 
+/// Sleep for `req`, whose `nsec` must be below a second.
+pub fn sys_nanosleep(req: &TimeSpec) -> isize {
+    syscall(SYSCALL_NANOSLEEP, [req as *const _ as usize, 0, 0])
+}
+
 pub fn sys_yield() -> isize {
     syscall(SYSCALL_YIELD, [0, 0, 0])
 }
@@ -158,6 +203,10 @@ pub fn sys_get_time(time: &TimeVal, tz: usize) -> isize {
     syscall(SYSCALL_GETTIMEOFDAY, [time as *const _ as usize, tz, 0])
 }
 
+pub fn sys_clock_gettime(clock_id: usize, time: &TimeSpec) -> isize {
+    syscall(SYSCALL_CLOCK_GETTIME, [clock_id, time as *const _ as usize, 0])
+}
+
 pub fn sys_getpid() -> isize {
     syscall(SYSCALL_GETPID, [0, 0, 0])
 }
@@ -217,6 +266,234 @@ pub fn sys_waittid(tid: usize) -> isize {
     syscall(SYSCALL_WAITTID, [tid, 0, 0])
 }
 
+pub fn sys_task_info_of(tid: usize, info: &TaskInfo) -> isize {
+    syscall(SYSCALL_TASK_INFO_OF, [tid, info as *const _ as usize, 0])
+}
+
+pub fn sys_task_name(tid: usize, buf: &mut [u8]) -> isize {
+    syscall(SYSCALL_TASK_NAME, [tid, buf.as_mut_ptr() as usize, buf.len()])
+}
+
+pub fn sys_get_clock_freq() -> isize {
+    syscall(SYSCALL_GET_CLOCK_FREQ, [0, 0, 0])
+}
+
+pub fn sys_get_ticks() -> isize {
+    syscall(SYSCALL_GET_TICKS, [0, 0, 0])
+}
+
+/// Allow the task on the harts whose bits are set in `mask`, which must
+/// not be 0. Only recorded for now, see `TaskInfo::affinity`.
+pub fn sys_set_affinity(mask: usize) -> isize {
+    syscall(SYSCALL_SET_AFFINITY, [mask, 0, 0])
+}
+
+/// Block until event `id`, one of 0 to 7, is signaled. Events are
+/// edge-triggered: signals sent before the wait are not seen.
+pub fn sys_wait_event(id: usize) -> isize {
+    syscall(SYSCALL_WAIT_EVENT, [id, 0, 0])
+}
+
+/// Wake every task waiting on event `id`, returning how many there were.
+pub fn sys_signal_event(id: usize) -> isize {
+    syscall(SYSCALL_SIGNAL_EVENT, [id, 0, 0])
+}
+
+/// Put `msg` in the mailbox of task `tid`. Fails if the task isn't alive
+/// or its mailbox is full.
+pub fn sys_send(tid: usize, msg: usize) -> isize {
+    syscall(SYSCALL_SEND, [tid, msg, 0])
+}
+
+/// Take the oldest message from this task's mailbox into `msg`, blocking
+/// until there is one.
+pub fn sys_recv(msg: &mut usize) -> isize {
+    syscall(SYSCALL_RECV, [msg as *mut _ as usize, 0, 0])
+}
+
+/// Number of tasks, one per linked app, with ids from 0.
+pub fn sys_get_task_count() -> isize {
+    syscall(SYSCALL_GET_TASK_COUNT, [0, 0, 0])
+}
+
+/// Most bytes of its user stack this task has used so far.
+pub fn sys_stack_usage() -> isize {
+    syscall(SYSCALL_STACK_USAGE, [0, 0, 0])
+}
+
+/// Have the kernel print the registers this task trapped with, and carry on.
+pub fn sys_dump_regs() -> isize {
+    syscall(SYSCALL_DUMP_REGS, [0, 0, 0])
+}
+
+/// Make the kernel fault while handling this syscall, which it reports
+/// with its registers before panicking. Only with its `fault-injection`
+/// feature, -1 otherwise.
+pub fn sys_kernel_fault() -> isize {
+    syscall(SYSCALL_KERNEL_FAULT, [0, 0, 0])
+}
+
+/// Have the kernel fail a `kassert!` on any nonzero `value`, which it
+/// reports along with this task's registers before shutting down. Only
+/// with its `fault-injection` feature, -1 otherwise.
+pub fn sys_kernel_assert(value: usize) -> isize {
+    syscall(SYSCALL_KERNEL_ASSERT, [value, 0, 0])
+}
+
+/// Make the kernel panic while handling this syscall, which it reports
+/// along with this task before shutting down. Only with its
+/// `fault-injection` feature, -1 otherwise.
+pub fn sys_kernel_panic() -> isize {
+    syscall(SYSCALL_KERNEL_PANIC, [0, 0, 0])
+}
+
+/// Like `sys_kernel_panic`, but with the kernel's task manager locked, so
+/// that its report can't look at the task. Only with its `fault-injection`
+/// feature, -1 otherwise.
+pub fn sys_kernel_panic_locked() -> isize {
+    syscall(SYSCALL_KERNEL_PANIC_LOCKED, [0, 0, 0])
+}
+
+/// Overwrite the guard at the bottom of this task's kernel stack, as an
+/// overflow would, so that the kernel panics at the next syscall or
+/// interrupt. Only with its `fault-injection` feature, -1 otherwise.
+pub fn sys_clobber_stack_guard() -> isize {
+    syscall(SYSCALL_CLOBBER_STACK_GUARD, [0, 0, 0])
+}
+
+/// Overwrite the canary of this task's saved context, so that the kernel
+/// panics at the next task switch. Only with its `context-canary` and
+/// `fault-injection` features, -1 otherwise.
+pub fn sys_clobber_context_canary() -> isize {
+    syscall(SYSCALL_CLOBBER_CONTEXT_CANARY, [0, 0, 0])
+}
+
+/// Copy the last syscalls made by any task into `entries`, oldest first,
+/// returning how many were copied. The kernel remembers the last 256, this
+/// one included.
+pub fn sys_dump_trace(entries: &mut [TraceEntry]) -> isize {
+    syscall(SYSCALL_DUMP_TRACE, [entries.as_mut_ptr() as usize, entries.len(), 0])
+}
+
+/// Copy the name of syscall `id` into `buf`, returning its length, or -1
+/// for an unknown syscall or a too small buffer.
+pub fn sys_syscall_name(id: usize, buf: &mut [u8]) -> isize {
+    syscall(SYSCALL_SYSCALL_NAME, [id, buf.as_mut_ptr() as usize, buf.len()])
+}
+
+/// Reboot the machine, cold with `mode` 1 or warm with 2. Only apps the
+/// kernel was built to trust may, -1 for any other.
+pub fn sys_reboot(mode: usize) -> isize {
+    syscall(SYSCALL_REBOOT, [mode, 0, 0])
+}
+
+/// Fill `param` with the scheduling parameters of task `tid`.
+pub fn sys_sched_getparam(tid: usize, param: &mut SchedParam) -> isize {
+    syscall(SYSCALL_SCHED_GETPARAM, [tid, param as *mut _ as usize, 0])
+}
+
+/// Set the scheduling parameters of task `tid`. -1 if `param.policy` isn't
+/// the kernel's, the priority is below 2, or the tickets are 0 or more than
+/// `u32::MAX`.
+pub fn sys_sched_setparam(tid: usize, param: &SchedParam) -> isize {
+    syscall(SYSCALL_SCHED_SETPARAM, [tid, param as *const _ as usize, 0])
+}
+
+/// Milliseconds since the kernel finished booting.
+pub fn sys_uptime() -> isize {
+    syscall(SYSCALL_UPTIME, [0, 0, 0])
+}
+
+pub fn sys_sbrk(increment: isize) -> isize {
+    syscall(SYSCALL_SBRK, [increment as usize, 0, 0])
+}
+
+/// Fill `buf` with pseudo-random bytes, from a stream of the caller's own.
+pub fn sys_getrandom(buf: &mut [u8]) -> isize {
+    syscall(SYSCALL_GETRANDOM, [buf.as_mut_ptr() as usize, buf.len(), 0])
+}
+
+pub fn sys_trap_stats(stats: &TrapStats) -> isize {
+    syscall(SYSCALL_TRAP_STATS, [stats as *const _ as usize, 0, 0])
+}
+
+/// Set a deadline `ticks` clocks from now. Fails unless the kernel
+/// schedules earliest deadline first.
+pub fn sys_set_deadline(ticks: usize) -> isize {
+    syscall(SYSCALL_SET_DEADLINE, [ticks, 0, 0])
+}
+
+/// Get killed after using `clocks` of CPU time in total, or never if 0.
+pub fn sys_set_cpu_limit(clocks: usize) -> isize {
+    syscall(SYSCALL_SET_CPU_LIMIT, [clocks, 0, 0])
+}
+
+/// Fill `briefs` with a line per task, returning how many were written.
+pub fn sys_list_tasks(briefs: &mut [TaskBrief]) -> isize {
+    syscall(SYSCALL_LIST_TASKS, [briefs.as_mut_ptr() as usize, briefs.len(), 0])
+}
+
+/// Fire an alarm every `ms` milliseconds, or stop it if 0.
+pub fn sys_set_alarm(ms: usize) -> isize {
+    syscall(SYSCALL_SET_ALARM, [ms, 0, 0])
+}
+
+/// How many times the alarm has fired since the last poll.
+pub fn sys_poll_alarm() -> isize {
+    syscall(SYSCALL_POLL_ALARM, [0, 0, 0])
+}
+
+/// Call `handler` with the number of alarms fired when the alarm fires,
+/// or stop if 0. The handler must end with `sys_sigreturn`.
+pub fn sys_set_alarm_handler(handler: usize) -> isize {
+    syscall(SYSCALL_SET_ALARM_HANDLER, [handler, 0, 0])
+}
+
+/// Leave the alarm handler, resuming what it interrupted. Only returns,
+/// with -1, outside a handler.
+pub fn sys_sigreturn() -> isize {
+    syscall(SYSCALL_SIGRETURN, [0, 0, 0])
+}
+
+/// Clocks the CPU has sat idle, with no task ready, since boot.
+pub fn sys_idle_time() -> isize {
+    syscall(SYSCALL_IDLE_TIME, [0, 0, 0])
+}
+
+/// Hold `tickets` lottery tickets. Fails for 0 or more than `u32::MAX`, or
+/// unless the kernel schedules by lottery.
+pub fn sys_set_tickets(tickets: usize) -> isize {
+    syscall(SYSCALL_SET_TICKETS, [tickets, 0, 0])
+}
+
+/// Yield to task `tid` if it is ready, or as `sys_yield` does with -1 if not.
+pub fn sys_yield_to(tid: usize) -> isize {
+    syscall(SYSCALL_YIELD_TO, [tid, 0, 0])
+}
+
+/// End task `tid` as if it exited with -1, or the caller if it is `tid`.
+pub fn sys_kill(tid: usize) -> isize {
+    syscall(SYSCALL_KILL, [tid, 0, 0])
+}
+
+/// Chapter 3 flavour of `spawn`: runs preloaded app `app_id` again,
+/// returning its task id, which is always `app_id`. The caller becomes its
+/// parent.
+///
+/// Each app has a single slot for its image and stacks, so only one run of
+/// it can exist at a time: -1 while the last one is still running or
+/// hasn't been reaped, as well as if there is no such app.
+pub fn sys_spawn_app(app_id: usize) -> isize {
+    syscall(SYSCALL_SPAWN, [app_id, 0, 0])
+}
+
+/// Chapter 3 flavour of `waittid`: copies out the exit code of an exited task.
+/// For a child of the caller it blocks until the child exits, and the child
+/// hands the CPU straight back on exit.
+pub fn sys_waittid_exit_code(tid: usize, exit_code: &mut i32) -> isize {
+    syscall(SYSCALL_WAITTID, [tid, exit_code as *mut _ as usize, 0])
+}
+
 pub fn sys_mutex_create(blocking: bool) -> isize {
     syscall(SYSCALL_MUTEX_CREATE, [blocking as usize, 0, 0])
 }
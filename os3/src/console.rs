@@ -1,12 +1,95 @@
 use core::fmt;
 // use crate::sys_write;
-use crate::sbi::console_putchar;
+use crate::sbi::{console_getchar, console_write};
+use crate::sync::InterruptSafeMutex;
+use crate::task::current_task_id;
+
+/// Messages below this level are compiled out.
+pub const LOG_LEVEL: LogLevel = LogLevel::Info;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl LogLevel {
+    fn tag(self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Trace => "TRACE",
+        }
+    }
+
+    /// ANSI foreground color code.
+    fn color(self) -> u8 {
+        match self {
+            LogLevel::Error => 31,
+            LogLevel::Warn => 93,
+            LogLevel::Info => 34,
+            LogLevel::Debug => 32,
+            LogLevel::Trace => 90,
+        }
+    }
+}
+
+const LINE_BUFFER_SIZE: usize = 256;
+
+/// Console output waiting for the end of its line, so that a line takes one
+/// SBI call rather than one per byte where the SBI implementation allows.
+struct LineBuffer {
+    buf: [u8; LINE_BUFFER_SIZE],
+    len: usize,
+}
+
+impl LineBuffer {
+    const fn new() -> Self {
+        Self { buf: [0; LINE_BUFFER_SIZE], len: 0 }
+    }
+
+    fn push(&mut self, c: u8) {
+        self.buf[self.len] = c;
+        self.len += 1;
+        if c == b'\n' || self.len == LINE_BUFFER_SIZE {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        console_write(&self.buf[..self.len]);
+        self.len = 0;
+    }
+}
+
+static LINE_BUFFER: InterruptSafeMutex<LineBuffer> = InterruptSafeMutex::new(LineBuffer::new());
+
+fn write_bytes(bytes: &[u8]) {
+    match LINE_BUFFER.try_lock() {
+        Some(mut line_buffer) => bytes.iter().for_each(|&c| line_buffer.push(c)),
+        // Printing from a panic while printing; don't lose it.
+        None => console_write(bytes),
+    }
+}
+
+/// Write out the buffered output. Must be called before shutting down, or
+/// the last line may be lost if it is incomplete.
+pub fn flush() {
+    if let Some(mut line_buffer) = LINE_BUFFER.try_lock() {
+        line_buffer.flush();
+    }
+}
 
 struct Stdout;
 
 impl fmt::Write for Stdout {
     fn write_str(&mut self, s: &str) -> fmt::Result {
-        s.bytes().for_each(|c| console_putchar(c as usize));
+        write_bytes(s.as_bytes());
         Ok(())
     }
 }
@@ -16,6 +99,115 @@ pub fn print(args: fmt::Arguments) {
     Stdout.write_fmt(args).unwrap();
 }
 
+pub fn putchar(c: u8) {
+    write_bytes(&[c]);
+}
+
+/// Write the bytes of an app's stdout as they are, valid UTF-8 or not.
+pub fn write_stdout(bytes: &[u8]) {
+    write_bytes(bytes);
+}
+
+/// Write the bytes of an app's stderr. This is the same console as stdout
+/// for now, but kept apart so that it can be redirected on its own. Unlike
+/// stdout it isn't buffered.
+pub fn write_stderr(bytes: &[u8]) {
+    flush();
+    console_write(bytes);
+}
+
+/// First byte of every frame, see `write_framed`.
+pub const FRAME_MAGIC: u8 = 0xf5;
+
+/// Write `bytes` as one frame, so that a tool on the other end of the
+/// console can tell it apart from the text around it:
+///
+/// ```text
+/// +------+----------------------+-------------------+
+/// | 0xf5 | length, u32 LE       | `length` bytes    |
+/// +------+----------------------+-------------------+
+///   1 byte       4 bytes
+/// ```
+///
+/// Like stderr, frames aren't buffered, and any buffered text goes out
+/// before them, so they are never split by other output.
+///
+/// # Panics
+///
+/// If `bytes` is longer than `u32::MAX`.
+pub fn write_framed(bytes: &[u8]) {
+    let len = u32::try_from(bytes.len()).expect("frame too long");
+    let mut header = [FRAME_MAGIC; 5];
+    header[1..].copy_from_slice(&len.to_le_bytes());
+    flush();
+    console_write(&header);
+    console_write(bytes);
+}
+
+const HEXDUMP_ROW: usize = 16;
+
+/// Print `len` bytes from `ptr` like `hexdump -C`: each row is the offset,
+/// 16 bytes in hex, split in two groups of 8, and the bytes as ASCII with
+/// `.` for unprintable ones.
+///
+/// ```text
+/// 00000000  68 65 6c 6c 6f 0a 00 01  02 03 04 05 06 07 08 09  |hello...........|
+/// 00000010  7f 41                                             |.A|
+/// ```
+///
+/// # Safety
+///
+/// `[ptr, ptr + len)` must be readable.
+pub unsafe fn hexdump(ptr: *const u8, len: usize) {
+    let bytes = core::slice::from_raw_parts(ptr, len);
+    for (row, chunk) in bytes.chunks(HEXDUMP_ROW).enumerate() {
+        write_hexdump_row(&mut Stdout, row * HEXDUMP_ROW, chunk).unwrap();
+    }
+}
+
+/// One row of `hexdump`, of the bytes `chunk` found at `offset`.
+fn write_hexdump_row(out: &mut impl fmt::Write, offset: usize, chunk: &[u8]) -> fmt::Result {
+    write!(out, "{:08x} ", offset)?;
+    for i in 0..HEXDUMP_ROW {
+        if i % 8 == 0 {
+            out.write_char(' ')?;
+        }
+        match chunk.get(i) {
+            Some(byte) => write!(out, "{:02x} ", byte)?,
+            None => out.write_str("   ")?,
+        }
+    }
+    out.write_str(" |")?;
+    for &byte in chunk {
+        let c = if byte.is_ascii_graphic() || byte == b' ' { byte as char } else { '.' };
+        out.write_char(c)?;
+    }
+    out.write_str("|\n")
+}
+
+/// Return the next byte typed on the console without waiting for one.
+pub fn getchar() -> Option<u8> {
+    // Show a pending prompt before waiting for the answer.
+    flush();
+    match console_getchar() {
+        usize::MAX => None,
+        c => Some(c as u8),
+    }
+}
+
+pub fn log(level: LogLevel, args: fmt::Arguments) {
+    match current_task_id() {
+        Some(task_id) => print(format_args!(
+            "\x1b[{}m[{:>5}][task {}] {}\x1b[0m\n",
+            level.color(), level.tag(), task_id, args
+        )),
+        None => print(format_args!(
+            "\x1b[{}m[{:>5}] {}\x1b[0m\n",
+            level.color(), level.tag(), args
+        )),
+    }
+}
+
 #[macro_export]
 macro_rules! print {
     ($fmt: literal $(, $($arg: tt)+)?) => {
@@ -32,3 +224,72 @@ macro_rules! println {
         $crate::console::print(::core::format_args_nl!($fmt $(, $($arg)+)?))
     };
 }
+
+#[macro_export]
+macro_rules! log {
+    ($level: ident, $fmt: literal $(, $($arg: tt)+)?) => {
+        if ($crate::console::LogLevel::$level as usize) <= ($crate::console::LOG_LEVEL as usize) {
+            $crate::console::log(
+                $crate::console::LogLevel::$level,
+                ::core::format_args!($fmt $(, $($arg)+)?),
+            )
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! log_error {
+    ($($arg: tt)+) => { $crate::log!(Error, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! log_warn {
+    ($($arg: tt)+) => { $crate::log!(Warn, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! log_info {
+    ($($arg: tt)+) => { $crate::log!(Info, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! log_debug {
+    ($($arg: tt)+) => { $crate::log!(Debug, $($arg)+) };
+}
+
+#[macro_export]
+macro_rules! log_trace {
+    ($($arg: tt)+) => { $crate::log!(Trace, $($arg)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hexdump_rows(bytes: &[u8]) -> String {
+        let mut out = String::new();
+        for (row, chunk) in bytes.chunks(HEXDUMP_ROW).enumerate() {
+            write_hexdump_row(&mut out, row * HEXDUMP_ROW, chunk).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn hexdump_full_and_partial_rows() {
+        let bytes = b"hello\n\x00\x01\x02\x03\x04\x05\x06\x07\x08\x09\x7fA";
+        assert_eq!(
+            "00000000  68 65 6c 6c 6f 0a 00 01  02 03 04 05 06 07 08 09  |hello...........|\n\
+             00000010  7f 41                                             |.A|\n",
+            hexdump_rows(bytes)
+        );
+    }
+
+    #[test]
+    fn hexdump_shows_only_printable_ascii() {
+        let bytes = [b' ', b'~', 0x80, 0xff, b'\t', b'0'];
+        assert_eq!(
+            "00000000  20 7e 80 ff 09 30                                 | ~...0|\n",
+            hexdump_rows(&bytes)
+        );
+    }
+}
@@ -1,9 +1,25 @@
+use crate::console;
 use crate::println;
-use crate::sbi::shutdown;
+use crate::sbi::{system_reset, ResetReason, ResetType};
+use crate::syscall::print_trace;
+use crate::task::{current_task_id, TASK_MANAGER};
 use core::panic::PanicInfo;
+use core::sync::atomic::{AtomicBool, Ordering};
+use riscv::register::sepc;
 
+static PANICKING: AtomicBool = AtomicBool::new(false);
+
+/// Nothing here may take a lock with `lock()`: the panic may have happened
+/// with it held, and `InterruptSafeMutex` panics on re-entry, which would
+/// come straight back here. Locks are only tried.
 #[panic_handler]
 fn panic_handler(info: &PanicInfo) -> ! {
+    if PANICKING.swap(true, Ordering::Relaxed) {
+        // Panicked while reporting a panic. Get out with what is known.
+        println!("panic while panicking: {}", info);
+        console::flush();
+        system_reset(ResetType::Shutdown, ResetReason::SystemFailure);
+    }
     if let Some(location) = info.location() {
         println!(
             "panic in file `{}` at line {}: {}",
@@ -14,5 +30,37 @@ fn panic_handler(info: &PanicInfo) -> ! {
     } else {
         println!("panic: {}", info)
     }
-    shutdown();
+    print_task_state();
+    print_trace();
+    console::flush();
+    system_reset(ResetType::Shutdown, ResetReason::SystemFailure);
+}
+
+/// Print which task was running when the kernel panicked.
+fn print_task_state() {
+    // `TASK_MANAGER` is set up before the first task runs. Touching it
+    // earlier could mean waiting for its own initialization to finish.
+    let task_id = match current_task_id() {
+        Some(task_id) => task_id,
+        None => {
+            println!("no task has run yet");
+            return;
+        }
+    };
+    // The last trap came from the task, unless the kernel took one since.
+    let sepc = sepc::read();
+    // The panic may have happened with the lock held.
+    match TASK_MANAGER.try_lock() {
+        Some(task_mgr) => println!(
+            "current task `{}` ({}, {:?}), sepc = {:#x}",
+            task_mgr.task_name(task_id).unwrap_or("?"),
+            task_id,
+            task_mgr.current_tcb().status,
+            sepc
+        ),
+        None => println!(
+            "current task `{}` (task info unavailable: lock held), sepc = {:#x}",
+            task_id, sepc
+        ),
+    }
 }
@@ -1,22 +1,30 @@
-#![no_std]
-#![no_main]
+#![cfg_attr(not(test), no_std)]
+#![cfg_attr(not(test), no_main)]
 #![feature(format_args_nl)]
 #![feature(sync_unsafe_cell)]
 #![feature(naked_functions)]
 #![feature(derive_default_enum)]
+#![feature(asm_const)]
 
 // pub mod batch;
 pub mod console;
+#[cfg(not(test))]
 pub mod lang_items;
+pub mod macros;
+pub mod rand;
 pub mod sbi;
+pub mod sync;
 pub mod syscall;
 pub mod trap;
 pub mod task;
 pub mod time;
+pub mod util;
 
+#[cfg(not(test))]
 use core::arch::global_asm;
 
-global_asm!(include_str!("entry.S"));
+#[cfg(not(test))]
+global_asm!(include_str!("entry.S"), BOOT_STACK_SIZE = const task::BOOT_STACK_SIZE);
 extern "C" {
     fn sbss();
     fn ebss();
@@ -26,13 +34,40 @@ pub fn clear_bss() {
     (sbss as usize..ebss as usize).for_each(|addr| unsafe { (addr as *mut u8).write_volatile(0) })
 }
 
+/// Check that `clear_bss` left every byte of .bss zero, and panic with the
+/// first that isn't. Only with debug logging on, as it reads the whole range
+/// again.
+///
+/// Zeroing can't fail on its own, but wrong `sbss`/`ebss` in linker.ld can
+/// make it: a range over the boot stack, say, gets written again by the
+/// very calls that clear and check it. To see the check fire, put
+/// `unsafe { (ebss as *mut u8).sub(1).write_volatile(1) }` between the two
+/// calls in `init`, and set `console::LOG_LEVEL` to `Debug`.
+pub fn verify_bss() {
+    if console::LogLevel::Debug as usize > console::LOG_LEVEL as usize {
+        return;
+    }
+    let dirty = (sbss as usize..ebss as usize).find(|&addr| unsafe { (addr as *const u8).read_volatile() } != 0);
+    if let Some(addr) = dirty {
+        panic!("byte at {:#x} of .bss [{:#x}, {:#x}) isn't zero after clearing it", addr, sbss as usize, ebss as usize);
+    }
+}
+
 pub fn init() {
     clear_bss();
+    verify_bss();
+    task::check_kernel_end();
+    sbi::init();
     trap::init();
 
     unsafe {
         // Avoid timer interrupt during the init.
         riscv::register::sstatus::clear_sie();
-        riscv::register::sie::set_stimer();
+        if task::PREEMPTIVE {
+            riscv::register::sie::set_stimer();
+        }
+        // Let apps use floating-point instructions, see `TaskManager::switch_fp`.
+        riscv::register::sstatus::set_fs(riscv::register::sstatus::FS::Initial);
     }
+    time::mark_boot();
 }
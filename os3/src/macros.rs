@@ -0,0 +1,45 @@
+use core::fmt;
+
+use crate::console;
+use crate::println;
+use crate::sbi::{system_reset, ResetReason, ResetType};
+use crate::task::current_task_id;
+use crate::trap::current_trap_context;
+
+/// Like `assert!`, but a failure also prints the current task and the
+/// registers it last trapped with, then shuts down instead of panicking.
+///
+/// Nothing on the way takes a lock, so it may fail with `TASK_MANAGER` held.
+#[macro_export]
+macro_rules! kassert {
+    ($cond: expr $(,)?) => {
+        $crate::kassert!($cond, "{}", ::core::stringify!($cond))
+    };
+    ($cond: expr, $fmt: literal $(, $($arg: tt)+)?) => {
+        if !$cond {
+            $crate::macros::kassert_failed(
+                ::core::file!(),
+                ::core::line!(),
+                ::core::format_args!($fmt $(, $($arg)+)?),
+            );
+        }
+    };
+}
+
+#[cold]
+pub fn kassert_failed(file: &str, line: u32, msg: fmt::Arguments) -> ! {
+    println!("[kernel] assertion failed in file `{}` at line {}: {}", file, line, msg);
+    match current_task_id() {
+        Some(task_id) => println!("[kernel] current task: {}", task_id),
+        None => println!("[kernel] no task has run yet"),
+    }
+    match current_trap_context() {
+        Some(cx) => {
+            println!("[kernel] registers it last trapped with:");
+            cx.dump();
+        }
+        None => println!("[kernel] it hasn't trapped yet"),
+    }
+    console::flush();
+    system_reset(ResetType::Shutdown, ResetReason::SystemFailure);
+}
@@ -1,5 +1,8 @@
 use constants::*;
+#[cfg(not(test))]
 use core::arch::asm;
+use core::sync::atomic::{AtomicBool, Ordering};
+use crate::log_info;
 
 #[allow(unused)]
 mod constants {
@@ -12,8 +15,67 @@ mod constants {
     pub const SBI_REMOTE_SFENCE_VMA: usize = 6;
     pub const SBI_REMOTE_SFENCE_VMA_ASID: usize = 7;
     pub const SBI_SHUTDOWN: usize = 8;
+
+    pub const EID_BASE: usize = 0x10;
+    pub const FID_GET_SPEC_VERSION: usize = 0;
+    pub const FID_PROBE_EXTENSION: usize = 3;
+
+    pub const EID_TIME: usize = 0x54494d45;
+    pub const FID_SET_TIMER: usize = 0;
+
+    pub const EID_IPI: usize = 0x735049;
+    pub const EID_RFENCE: usize = 0x52464e43;
+    pub const EID_HSM: usize = 0x48534d;
+
+    pub const EID_SRST: usize = 0x53525354;
+    pub const FID_SYSTEM_RESET: usize = 0;
+
+    pub const EID_DBCN: usize = 0x4442434e;
+    pub const FID_CONSOLE_WRITE: usize = 0;
+}
+
+/// Extensions reported at boot.
+const KNOWN_EXTENSIONS: [(&str, usize); 6] = [
+    ("TIME", EID_TIME),
+    ("IPI", EID_IPI),
+    ("RFENCE", EID_RFENCE),
+    ("HSM", EID_HSM),
+    ("SRST", EID_SRST),
+    ("DBCN", EID_DBCN),
+];
+
+/// Whether the extensions used instead of legacy calls are present, as
+/// probed by `init`. Until then only legacy calls are made.
+static HAS_TIME: AtomicBool = AtomicBool::new(false);
+static HAS_SRST: AtomicBool = AtomicBool::new(false);
+static HAS_DBCN: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetType {
+    Shutdown = 0,
+    ColdReboot = 1,
+    WarmReboot = 2,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetReason {
+    NoReason = 0,
+    SystemFailure = 1,
+}
+
+/// Result of a non-legacy SBI call. `error` is 0 on success.
+struct SbiRet {
+    error: isize,
+    value: usize,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpecVersion {
+    pub major: usize,
+    pub minor: usize,
+}
+
+#[cfg(not(test))]
 #[inline(always)]
 fn sbi_call(which: usize, arg0: usize, arg1: usize, arg2: usize) -> usize {
     let mut ret;
@@ -30,15 +92,126 @@ fn sbi_call(which: usize, arg0: usize, arg1: usize, arg2: usize) -> usize {
     ret
 }
 
+/// Call function `fid` of extension `eid` with the SBI v0.2 calling convention.
+#[cfg(not(test))]
+#[inline(always)]
+fn sbi_call_ext(eid: usize, fid: usize, arg0: usize, arg1: usize, arg2: usize) -> SbiRet {
+    let (error, value);
+    unsafe {
+        asm!(
+            "ecall",
+            inlateout("x10") arg0 => error,
+            inlateout("x11") arg1 => value,
+            in("x12") arg2,
+            in("x16") fid,
+            in("x17") eid,
+        );
+    }
+    SbiRet { error, value }
+}
+
+/// Host unit tests have no SBI to call, so legacy calls do nothing and no
+/// extension is there.
+#[cfg(test)]
+fn sbi_call(_which: usize, _arg0: usize, _arg1: usize, _arg2: usize) -> usize {
+    0
+}
+
+#[cfg(test)]
+fn sbi_call_ext(_eid: usize, _fid: usize, _arg0: usize, _arg1: usize, _arg2: usize) -> SbiRet {
+    // SBI_ERR_NOT_SUPPORTED
+    SbiRet { error: -2, value: 0 }
+}
+
+/// Whether the SBI implementation provides extension `eid`. Always false
+/// for legacy (v0.1) implementations, which lack the base extension.
+pub fn probe_extension(eid: usize) -> bool {
+    let ret = sbi_call_ext(EID_BASE, FID_PROBE_EXTENSION, eid, 0, 0);
+    ret.error == 0 && ret.value != 0
+}
+
+/// The implemented SBI specification version, 0.1 for legacy implementations.
+pub fn spec_version() -> SpecVersion {
+    let ret = sbi_call_ext(EID_BASE, FID_GET_SPEC_VERSION, 0, 0, 0);
+    if ret.error != 0 {
+        return SpecVersion { major: 0, minor: 1 };
+    }
+    SpecVersion {
+        major: (ret.value >> 24) & 0x7f,
+        minor: ret.value & 0xff_ffff,
+    }
+}
+
+/// Detect the available extensions and report them.
+pub fn init() {
+    HAS_TIME.store(probe_extension(EID_TIME), Ordering::Relaxed);
+    HAS_SRST.store(probe_extension(EID_SRST), Ordering::Relaxed);
+    HAS_DBCN.store(probe_extension(EID_DBCN), Ordering::Relaxed);
+
+    let version = spec_version();
+    log_info!("SBI v{}.{}, extensions:", version.major, version.minor);
+    for (name, eid) in KNOWN_EXTENSIONS {
+        log_info!("  {:<6} {}", name, if probe_extension(eid) { "yes" } else { "no" });
+    }
+}
+
 pub fn console_putchar(c: usize) {
     sbi_call(SBI_CONSOLE_PUTCHAR, c, 0, 0);
 }
 
-pub fn shutdown() -> ! {
+/// Write `bytes` to the console, with as few calls as the debug console
+/// extension allows, or one legacy call per byte without it.
+pub fn console_write(mut bytes: &[u8]) {
+    if HAS_DBCN.load(Ordering::Relaxed) {
+        // Addresses are physical, which they all are without paging.
+        while !bytes.is_empty() {
+            let addr = bytes.as_ptr() as u64;
+            let ret = sbi_call_ext(
+                EID_DBCN, FID_CONSOLE_WRITE, bytes.len(), addr as usize, (addr >> 32) as usize,
+            );
+            // Writes may be partial.
+            if ret.error != 0 || ret.value == 0 {
+                break;
+            }
+            bytes = &bytes[ret.value.min(bytes.len())..];
+        }
+    }
+    bytes.iter().for_each(|&c| console_putchar(c as usize));
+}
+
+/// Return the next byte from the console, or `usize::MAX` (-1) if there is none.
+pub fn console_getchar() -> usize {
+    sbi_call(SBI_CONSOLE_GETCHAR, 0, 0, 0)
+}
+
+/// Shut down or reboot the machine through the SRST extension.
+///
+/// Without SRST, only the legacy shutdown is available, which is used
+/// whatever `reset_type` is.
+pub fn system_reset(reset_type: ResetType, reason: ResetReason) -> ! {
+    if HAS_SRST.load(Ordering::Relaxed) {
+        sbi_call_ext(EID_SRST, FID_SYSTEM_RESET, reset_type as usize, reason as usize, 0);
+    }
     sbi_call(SBI_SHUTDOWN, 0, 0, 0);
     unreachable!("It should have been shut down")
 }
 
+pub fn shutdown() -> ! {
+    system_reset(ResetType::Shutdown, ResetReason::NoReason)
+}
+
+/// Reboot the machine, cold or warm. Returns only without SRST, as the
+/// legacy calls can't reboot.
+pub fn reboot(reset_type: ResetType) {
+    if HAS_SRST.load(Ordering::Relaxed) {
+        system_reset(reset_type, ResetReason::NoReason);
+    }
+}
+
 pub fn set_timer(t: usize) {
-    sbi_call(SBI_SET_TIMER, t, 0, 0);
+    if HAS_TIME.load(Ordering::Relaxed) {
+        sbi_call_ext(EID_TIME, FID_SET_TIMER, t, 0, 0);
+    } else {
+        sbi_call(SBI_SET_TIMER, t, 0, 0);
+    }
 }
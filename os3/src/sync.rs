@@ -0,0 +1,120 @@
+use core::cell::UnsafeCell;
+use core::ops::{Deref, DerefMut};
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(not(test))]
+use riscv::register::sstatus;
+
+/// Host unit tests have no interrupts to mask.
+#[cfg(test)]
+mod sstatus {
+    pub struct Sstatus;
+
+    impl Sstatus {
+        pub fn sie(&self) -> bool {
+            false
+        }
+    }
+
+    pub fn read() -> Sstatus {
+        Sstatus
+    }
+
+    pub unsafe fn clear_sie() {}
+
+    pub unsafe fn set_sie() {}
+}
+
+/// How many `push_off` are not yet matched by a `pop_off`.
+static INTR_OFF_DEPTH: AtomicUsize = AtomicUsize::new(0);
+/// Whether interrupts were enabled before the outermost `push_off`.
+static INTR_WAS_ON: AtomicBool = AtomicBool::new(false);
+
+/// Disable supervisor interrupts. Calls nest: interrupts are only restored
+/// by the `pop_off` matching the outermost `push_off`.
+pub fn push_off() {
+    let sie = sstatus::read().sie();
+    unsafe {
+        sstatus::clear_sie();
+    }
+    if INTR_OFF_DEPTH.fetch_add(1, Ordering::Relaxed) == 0 {
+        INTR_WAS_ON.store(sie, Ordering::Relaxed);
+    }
+}
+
+pub fn pop_off() {
+    let depth = INTR_OFF_DEPTH.fetch_sub(1, Ordering::Relaxed);
+    assert!(depth > 0, "pop_off without push_off");
+    if depth == 1 && INTR_WAS_ON.load(Ordering::Relaxed) {
+        unsafe {
+            sstatus::set_sie();
+        }
+    }
+}
+
+/// A lock for the single-hart kernel that keeps supervisor interrupts
+/// disabled while it is held.
+///
+/// With interrupts off nothing can preempt the holder, so the lock never has
+/// to spin: finding it already locked means the kernel tried to take it
+/// re-entrantly, which would deadlock with a spin lock and is reported with a
+/// panic instead.
+pub struct InterruptSafeMutex<T> {
+    locked: AtomicBool,
+    data: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Sync for InterruptSafeMutex<T> {}
+
+pub struct InterruptSafeMutexGuard<'a, T> {
+    mutex: &'a InterruptSafeMutex<T>,
+}
+
+impl<T> InterruptSafeMutex<T> {
+    pub const fn new(data: T) -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            data: UnsafeCell::new(data),
+        }
+    }
+
+    pub fn lock(&self) -> InterruptSafeMutexGuard<T> {
+        push_off();
+        if self.locked.swap(true, Ordering::Acquire) {
+            panic!("InterruptSafeMutex is already locked");
+        }
+        InterruptSafeMutexGuard { mutex: self }
+    }
+
+    /// Like `lock`, but return `None` rather than panic if it is held, for
+    /// code that may run while it is, such as the panic handler.
+    pub fn try_lock(&self) -> Option<InterruptSafeMutexGuard<T>> {
+        push_off();
+        if self.locked.swap(true, Ordering::Acquire) {
+            pop_off();
+            return None;
+        }
+        Some(InterruptSafeMutexGuard { mutex: self })
+    }
+}
+
+impl<'a, T> Deref for InterruptSafeMutexGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.mutex.data.get() }
+    }
+}
+
+impl<'a, T> DerefMut for InterruptSafeMutexGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.mutex.data.get() }
+    }
+}
+
+impl<'a, T> Drop for InterruptSafeMutexGuard<'a, T> {
+    fn drop(&mut self) {
+        // Unlock before interrupts can be taken again.
+        self.mutex.locked.store(false, Ordering::Release);
+        pop_off();
+    }
+}
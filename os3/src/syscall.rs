@@ -1,19 +1,101 @@
+mod trace;
+
+use core::mem::size_of;
+
+use trace::{copy_trace, record_trace, TraceEntry};
+pub use trace::print_trace;
+use crate::console;
 use crate::print;
+use crate::println;
+use crate::log_info;
+use crate::sbi::{self, ResetType};
+use crate::log_debug;
 use crate::task::run_next_task;
+use crate::task::yield_to;
 use crate::task::exit_and_run_next;
+use crate::task::sleep_and_run_next;
+use crate::task::wait_event_and_run_next;
+use crate::task::recv_or_run_next;
+use crate::task::waittid_or_run_next;
 use crate::task::record_syscall;
+use crate::task::record_voluntary_switch;
 use crate::task::TASK_MANAGER;
 use crate::task::TaskStatus;
+use crate::task::TaskControlBlock;
+use crate::task::TaskStat;
+use crate::task::set_current_priority;
+use crate::task::set_current_deadline;
+use crate::task::set_current_cpu_limit;
+use crate::task::set_current_tickets;
+use crate::task::set_current_affinity;
+use crate::task::{current_fd, dup_current_fd, FdTarget};
+use crate::task::{set_current_alarm, poll_current_alarm};
+use crate::task::{set_current_alarm_handler, in_alarm_handler};
+use crate::task::{SchedPolicy, SCHED_POLICY};
+use crate::task::MIN_PRIORITY;
+use crate::task::MAX_TICKETS;
+use crate::task::check_user_ptr;
+use crate::task::current_task;
+use crate::task::current_is_privileged;
+use crate::task::idle_clocks;
+use crate::task::current_user_stack_usage;
 use crate::time;
+use crate::trap::{trap_stats, TrapStats};
 
-pub const STDOUT: usize = 1;
 pub const MAX_SYSCALL_NUM: usize = 500;
 
+pub const SYSCALL_DUP: usize = 24;
 pub const SYSCALL_EXIT: usize = 93;
+pub const SYSCALL_SLEEP: usize = 101;
+pub const SYSCALL_CLOCK_GETTIME: usize = 113;
+pub const SYSCALL_SCHED_SETPARAM: usize = 118;
+pub const SYSCALL_SCHED_GETPARAM: usize = 121;
+pub const SYSCALL_READ: usize = 63;
 pub const SYSCALL_WRITE: usize = 64;
 pub const SYSCALL_YIELD: usize = 124;
+pub const SYSCALL_KILL: usize = 129;
+pub const SYSCALL_SIGRETURN: usize = 139;
+pub const SYSCALL_SET_PRIORITY: usize = 140;
+pub const SYSCALL_REBOOT: usize = 142;
 pub const SYSCALL_GET_TIME: usize = 169;
+pub const SYSCALL_GETPID: usize = 172;
+pub const SYSCALL_SBRK: usize = 214;
+pub const SYSCALL_GETRANDOM: usize = 278;
+pub const SYSCALL_SPAWN: usize = 400;
 pub const SYSCALL_TASK_INFO: usize = 410;
+pub const SYSCALL_TASK_INFO_OF: usize = 411;
+pub const SYSCALL_TASK_NAME: usize = 412;
+pub const SYSCALL_GET_CLOCK_FREQ: usize = 413;
+pub const SYSCALL_GET_TICKS: usize = 414;
+pub const SYSCALL_TRAP_STATS: usize = 415;
+pub const SYSCALL_SET_DEADLINE: usize = 416;
+pub const SYSCALL_SET_CPU_LIMIT: usize = 417;
+pub const SYSCALL_YIELD_TO: usize = 418;
+pub const SYSCALL_SET_TICKETS: usize = 419;
+pub const SYSCALL_LIST_TASKS: usize = 420;
+pub const SYSCALL_IDLE_TIME: usize = 421;
+pub const SYSCALL_NANOSLEEP: usize = 422;
+pub const SYSCALL_SET_ALARM: usize = 423;
+pub const SYSCALL_POLL_ALARM: usize = 424;
+pub const SYSCALL_SET_ALARM_HANDLER: usize = 425;
+pub const SYSCALL_UPTIME: usize = 426;
+pub const SYSCALL_SET_AFFINITY: usize = 427;
+pub const SYSCALL_WAIT_EVENT: usize = 428;
+pub const SYSCALL_SIGNAL_EVENT: usize = 429;
+pub const SYSCALL_SEND: usize = 430;
+pub const SYSCALL_RECV: usize = 431;
+pub const SYSCALL_GET_TASK_COUNT: usize = 432;
+pub const SYSCALL_STACK_USAGE: usize = 433;
+pub const SYSCALL_DUMP_REGS: usize = 434;
+pub const SYSCALL_KERNEL_FAULT: usize = 435;
+pub const SYSCALL_SYSCALL_NAME: usize = 436;
+pub const SYSCALL_KERNEL_ASSERT: usize = 437;
+pub const SYSCALL_DUMP_TRACE: usize = 438;
+pub const SYSCALL_CLOBBER_STACK_GUARD: usize = 439;
+pub const SYSCALL_CLOBBER_CONTEXT_CANARY: usize = 440;
+pub const SYSCALL_KERNEL_PANIC: usize = 441;
+pub const SYSCALL_KERNEL_PANIC_LOCKED: usize = 442;
+pub const SYSCALL_WAITTID: usize = 462;
 
 #[repr(C)]
 #[derive(Debug)]
@@ -22,66 +104,613 @@ struct TimeVal {
     pub usec: usize,
 }
 
+#[repr(C)]
+#[derive(Debug)]
+struct TimeSpec {
+    pub sec: usize,
+    pub nsec: usize,
+}
+
 #[allow(dead_code)]
-// #[repr(C)]
+#[repr(C)]
 #[derive(Debug)]
 struct TaskInfo {
     pub status: TaskStatus,
     pub syscall_times: [u32; MAX_SYSCALL_NUM],
-    pub time: usize
+    pub time: usize,
+    pub kernel_clocks: usize,
+    pub user_clocks: usize,
+    pub schedule_count: usize,
+    pub mlfq_level: usize,
+    /// Only meaningful for a zombie.
+    pub exit_code: i32,
+    /// Clocks from being loaded to first running, 0 until then.
+    pub dispatch_latency: usize,
+    pub tickets: usize,
+    pub voluntary_switches: usize,
+    pub involuntary_switches: usize,
+    pub affinity: usize,
+    /// CPU time up to the task's last switch, in clocks and in ms.
+    pub cpu_clocks: usize,
+    pub cpu_time_ms: usize,
+}
+
+/// A line of a process table, see `SYSCALL_LIST_TASKS`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+struct TaskBrief {
+    pub id: usize,
+    pub status: TaskStatus,
+    pub cpu_clocks: usize,
+    pub priority: usize,
+}
+
+/// A task's scheduling parameters, see `SYSCALL_SCHED_GETPARAM`.
+#[repr(C)]
+#[derive(Debug)]
+struct SchedParam {
+    /// `SchedPolicy` as a number, in the order of its variants. The same
+    /// for every task, and can't be changed.
+    pub policy: usize,
+    pub priority: usize,
+    pub tickets: usize,
+    /// In clocks since boot, 0 for none.
+    pub deadline: usize,
+}
+
+impl TaskInfo {
+    fn fill(&mut self, tcb: &TaskControlBlock, stat: &TaskStat) {
+        self.status = tcb.status;
+        self.syscall_times = stat.syscall_times;
+        self.time = clocks_to_usize(time::clocks_to_ms(stat.real_time()));
+        self.kernel_clocks = clocks_to_usize(stat.kernel_clocks);
+        self.user_clocks = clocks_to_usize(stat.user_clocks);
+        self.schedule_count = stat.schedule_count;
+        self.mlfq_level = tcb.level;
+        self.exit_code = tcb.exit_code;
+        self.dispatch_latency = clocks_to_usize(stat.dispatch_latency().unwrap_or(0));
+        self.tickets = tcb.tickets;
+        self.voluntary_switches = stat.voluntary_switches;
+        self.involuntary_switches = stat.involuntary_switches;
+        self.affinity = tcb.affinity;
+        self.cpu_clocks = clocks_to_usize(stat.cpu_clocks);
+        self.cpu_time_ms = clocks_to_usize(stat.cpu_time_ms());
+    }
+}
+
+/// A u64 count for a usize field of the user ABI, saturating on RV32
+/// rather than wrapping, so that it never appears to go back.
+fn clocks_to_usize(clocks: u64) -> usize {
+    usize::try_from(clocks).unwrap_or(usize::MAX)
+}
+
+/// The name of syscall `id`, as the user library calls it without the
+/// `sys_` prefix, or "unknown".
+pub fn name(id: usize) -> &'static str {
+    match id {
+        SYSCALL_DUP => "dup",
+        SYSCALL_EXIT => "exit",
+        SYSCALL_SLEEP => "sleep",
+        SYSCALL_CLOCK_GETTIME => "clock_gettime",
+        SYSCALL_SCHED_SETPARAM => "sched_setparam",
+        SYSCALL_SCHED_GETPARAM => "sched_getparam",
+        SYSCALL_READ => "read",
+        SYSCALL_WRITE => "write",
+        SYSCALL_YIELD => "yield",
+        SYSCALL_KILL => "kill",
+        SYSCALL_SIGRETURN => "sigreturn",
+        SYSCALL_SET_PRIORITY => "set_priority",
+        SYSCALL_REBOOT => "reboot",
+        SYSCALL_GET_TIME => "get_time",
+        SYSCALL_GETPID => "getpid",
+        SYSCALL_SBRK => "sbrk",
+        SYSCALL_GETRANDOM => "getrandom",
+        SYSCALL_SPAWN => "spawn",
+        SYSCALL_TASK_INFO => "task_info",
+        SYSCALL_TASK_INFO_OF => "task_info_of",
+        SYSCALL_TASK_NAME => "task_name",
+        SYSCALL_GET_CLOCK_FREQ => "get_clock_freq",
+        SYSCALL_GET_TICKS => "get_ticks",
+        SYSCALL_TRAP_STATS => "trap_stats",
+        SYSCALL_SET_DEADLINE => "set_deadline",
+        SYSCALL_SET_CPU_LIMIT => "set_cpu_limit",
+        SYSCALL_YIELD_TO => "yield_to",
+        SYSCALL_SET_TICKETS => "set_tickets",
+        SYSCALL_LIST_TASKS => "list_tasks",
+        SYSCALL_IDLE_TIME => "idle_time",
+        SYSCALL_NANOSLEEP => "nanosleep",
+        SYSCALL_SET_ALARM => "set_alarm",
+        SYSCALL_POLL_ALARM => "poll_alarm",
+        SYSCALL_SET_ALARM_HANDLER => "set_alarm_handler",
+        SYSCALL_UPTIME => "uptime",
+        SYSCALL_SET_AFFINITY => "set_affinity",
+        SYSCALL_WAIT_EVENT => "wait_event",
+        SYSCALL_SIGNAL_EVENT => "signal_event",
+        SYSCALL_SEND => "send",
+        SYSCALL_RECV => "recv",
+        SYSCALL_GET_TASK_COUNT => "get_task_count",
+        SYSCALL_STACK_USAGE => "stack_usage",
+        SYSCALL_DUMP_REGS => "dump_regs",
+        SYSCALL_KERNEL_FAULT => "kernel_fault",
+        SYSCALL_SYSCALL_NAME => "syscall_name",
+        SYSCALL_KERNEL_ASSERT => "kernel_assert",
+        SYSCALL_DUMP_TRACE => "dump_trace",
+        SYSCALL_CLOBBER_STACK_GUARD => "clobber_stack_guard",
+        SYSCALL_CLOBBER_CONTEXT_CANARY => "clobber_context_canary",
+        SYSCALL_KERNEL_PANIC => "kernel_panic",
+        SYSCALL_KERNEL_PANIC_LOCKED => "kernel_panic_locked",
+        SYSCALL_WAITTID => "waittid",
+        _ => "unknown",
+    }
+}
+
+/// Log, at debug level, how many times each syscall was made by name,
+/// leaving out those never made.
+pub fn log_histogram(syscall_times: &[u32]) {
+    for (id, &times) in syscall_times.iter().enumerate() {
+        if times != 0 {
+            log_debug!("    {:>4} {:<18} {}", id, name(id), times);
+        }
+    }
 }
 
 pub fn syscall(id: usize, args: [usize; 3]) -> isize {
     record_syscall(id);
+    record_trace(current_task(), id, args[0]);
 
     match id {
         SYSCALL_EXIT => {
-            exit_and_run_next();
+            exit_and_run_next(args[0] as i32);
             0
         }
+        SYSCALL_READ => {
+            if current_fd(args[0]) != Some(FdTarget::Stdin) {
+                return -1;
+            }
+            if !check_user_ptr(args[1], args[2]) {
+                return -1;
+            }
+            let buffer = unsafe { core::slice::from_raw_parts_mut(args[1] as *mut u8, args[2]) };
+            read_line(buffer) as isize
+        }
         SYSCALL_WRITE => {
             let fd = args[0];
-            if fd != STDOUT {
-                panic!("unsupported fd in syscall write");
-            }
-
             let buffer_ptr = args[1];
             let buffer_size = args[2];
-            let buffer = unsafe { core::slice::from_raw_parts(buffer_ptr as *const u8, args[2]) };
+            let target = current_fd(fd);
+            if !matches!(target, Some(FdTarget::Stdout | FdTarget::Stderr | FdTarget::Framed))
+                || !check_user_ptr(buffer_ptr, buffer_size)
+                || buffer_size > u32::MAX as usize
+            {
+                return -1;
+            }
+            let buffer = unsafe { core::slice::from_raw_parts(buffer_ptr as *const u8, buffer_size) };
 
-            print!(
-                "{}",
-                core::str::from_utf8(buffer).expect("try to print non-utf8 str")
-            );
-            buffer_size as isize
+            match target {
+                Some(FdTarget::Stdout) => console::write_stdout(buffer),
+                Some(FdTarget::Framed) => console::write_framed(buffer),
+                _ => console::write_stderr(buffer),
+            }
+            buffer.len() as isize
+        }
+        SYSCALL_DUP => dup_current_fd(args[0]).map_or(-1, |fd| fd as isize),
+        SYSCALL_SLEEP => {
+            sleep_and_run_next(args[0].saturating_mul(time::CLOCKS_PER_MILLI_SEC));
+            0
+        }
+        SYSCALL_NANOSLEEP => {
+            let time_spec_ptr = args[0] as *const TimeSpec;
+            if !check_user_ptr(time_spec_ptr as usize, size_of::<TimeSpec>()) {
+                return -1;
+            }
+            let time_spec = unsafe { &*time_spec_ptr };
+            if time_spec.nsec >= time::NANO_PER_SEC {
+                return -1;
+            }
+            sleep_and_run_next(time::clocks_from_sec_nsec(time_spec.sec, time_spec.nsec));
+            0
         }
         SYSCALL_YIELD => {
-            // crate::println!("\nyield..");
+            // `move_to_next_task` puts the task back to Ready, and its `sepc`
+            // is already past the `ecall`, so it resumes right after it.
+            record_voluntary_switch();
             run_next_task();
             0
         }
+        SYSCALL_SET_PRIORITY => {
+            let prio = args[0] as isize;
+            if prio < MIN_PRIORITY as isize {
+                return -1;
+            }
+            set_current_priority(prio as usize);
+            prio
+        }
+        SYSCALL_SET_DEADLINE => {
+            // Deadlines only matter to EDF, so tell the caller it's off.
+            if SCHED_POLICY != SchedPolicy::Edf {
+                return -1;
+            }
+            set_current_deadline(args[0]);
+            0
+        }
+        SYSCALL_SET_TICKETS => {
+            // Like deadlines, tickets only matter to their own policy.
+            let tickets = args[0];
+            if SCHED_POLICY != SchedPolicy::Lottery || tickets == 0 || tickets > MAX_TICKETS {
+                return -1;
+            }
+            set_current_tickets(tickets);
+            tickets as isize
+        }
+        SYSCALL_SET_CPU_LIMIT => {
+            set_current_cpu_limit(args[0]);
+            0
+        }
         SYSCALL_GET_TIME => {
-            let t = time::get_time();
-            let time_val = unsafe { &mut *(args[0] as *mut TimeVal)};
-            time_val.sec = t / time::CLOCKS_PER_SEC;
-            // This funny formula is to work around percision issue of the CLOCKS_PER_MICRO_SEC,
-            // which should be 12.5 instead of 12.
-            time_val.usec = t % time::CLOCKS_PER_SEC / time::CLOCKS_PER_MILLI_SEC * 1000;
+            // args[1] is the timezone, which is ignored.
+            let time_val_ptr = args[0] as *mut TimeVal;
+            if !check_user_ptr(time_val_ptr as usize, size_of::<TimeVal>()) {
+                return -1;
+            }
+            let time_val = unsafe { &mut *time_val_ptr };
+            let us = time::get_time_us();
+            time_val.sec = us / time::MICRO_PER_SEC;
+            time_val.usec = us % time::MICRO_PER_SEC;
 
             0
         }
+        SYSCALL_CLOCK_GETTIME => {
+            // args[0] is the clock id. All clocks count from boot, so it's
+            // ignored.
+            let time_spec_ptr = args[1] as *mut TimeSpec;
+            if !check_user_ptr(time_spec_ptr as usize, size_of::<TimeSpec>()) {
+                return -1;
+            }
+            let time_spec = unsafe { &mut *time_spec_ptr };
+            let ns = time::get_time_ns();
+            time_spec.sec = (ns / time::NANO_PER_SEC as u64) as usize;
+            time_spec.nsec = (ns % time::NANO_PER_SEC as u64) as usize;
+            0
+        }
+        SYSCALL_GETPID => current_task() as isize,
+        SYSCALL_SBRK => match TASK_MANAGER.lock().sbrk(args[0] as isize) {
+            Some(old_brk) => old_brk as isize,
+            None => -1,
+        },
         SYSCALL_TASK_INFO => {
-            let task_info = unsafe { &mut *(args[0] as *mut TaskInfo) };
-
+            let task_info_ptr = args[0] as *mut TaskInfo;
             let task_mgr = TASK_MANAGER.lock();
-            let status = task_mgr.current_tcb().status;
-            let stat = task_mgr.current_stat();
-
-            task_info.status = status;
-            task_info.syscall_times = stat.syscall_times;
-            task_info.time = stat.real_time() / time::CLOCKS_PER_MILLI_SEC;
+            if !task_mgr.check_user_ptr(task_info_ptr as usize, size_of::<TaskInfo>()) {
+                return -1;
+            }
+            let task_info = unsafe { &mut *task_info_ptr };
+            task_info.fill(task_mgr.current_tcb(), task_mgr.current_stat());
+            0
+        }
+        SYSCALL_TASK_INFO_OF => {
+            let task_info_ptr = args[1] as *mut TaskInfo;
+            let task_mgr = TASK_MANAGER.lock();
+            if !task_mgr.check_user_ptr(task_info_ptr as usize, size_of::<TaskInfo>()) {
+                return -1;
+            }
+            let (tcb, stat) = match (task_mgr.tcb(args[0]), task_mgr.stat(args[0])) {
+                (Some(tcb), Some(stat)) => (tcb, stat),
+                _ => return -1,
+            };
+            // Reaped tasks are gone.
+            if matches!(tcb.status, TaskStatus::UnInit | TaskStatus::Exited) {
+                return -1;
+            }
+            let task_info = unsafe { &mut *task_info_ptr };
+            task_info.fill(tcb, stat);
             0
         }
-        unknown => panic!("unknown syscall `{}`", unknown),
+        SYSCALL_SCHED_GETPARAM => {
+            let param_ptr = args[1] as *mut SchedParam;
+            let task_mgr = TASK_MANAGER.lock();
+            if !task_mgr.check_user_ptr(param_ptr as usize, size_of::<SchedParam>()) {
+                return -1;
+            }
+            let tcb = match task_mgr.tcb(args[0]) {
+                Some(tcb) if !matches!(tcb.status, TaskStatus::UnInit | TaskStatus::Exited) => tcb,
+                _ => return -1,
+            };
+            let param = unsafe { &mut *param_ptr };
+            param.policy = SCHED_POLICY as usize;
+            param.priority = tcb.priority;
+            param.tickets = tcb.tickets;
+            param.deadline = tcb.deadline.unwrap_or(0);
+            0
+        }
+        SYSCALL_SCHED_SETPARAM => {
+            let param_ptr = args[1] as *const SchedParam;
+            let mut task_mgr = TASK_MANAGER.lock();
+            if !task_mgr.check_user_ptr(param_ptr as usize, size_of::<SchedParam>()) {
+                return -1;
+            }
+            let param = unsafe { &*param_ptr };
+            // The policy is picked at build time, and only restated here.
+            if param.policy != SCHED_POLICY as usize
+                || param.priority < MIN_PRIORITY
+                || param.tickets == 0
+                || param.tickets > MAX_TICKETS
+            {
+                return -1;
+            }
+            let deadline = (param.deadline != 0).then(|| param.deadline);
+            if !task_mgr.set_sched_param(args[0], param.priority, param.tickets, deadline) {
+                return -1;
+            }
+            0
+        }
+        SYSCALL_STACK_USAGE => current_user_stack_usage() as isize,
+        // Printed by the trap handler, which has the registers.
+        SYSCALL_DUMP_REGS => 0,
+        SYSCALL_REBOOT => {
+            let reset_type = match args[0] {
+                1 => ResetType::ColdReboot,
+                2 => ResetType::WarmReboot,
+                _ => return -1,
+            };
+            if !current_is_privileged() {
+                return -1;
+            }
+            log_info!("application `{}` rebooted the machine", current_task());
+            console::flush();
+            sbi::reboot(reset_type);
+            // No SRST to reboot with.
+            -1
+        }
+        // Address 0 is outside RAM and every device, so the load faults.
+        #[cfg(feature = "fault-injection")]
+        SYSCALL_KERNEL_FAULT => {
+            #[cfg(not(test))]
+            unsafe {
+                core::arch::asm!("lw zero, 0(zero)");
+            }
+            0
+        }
+        #[cfg(feature = "fault-injection")]
+        SYSCALL_KERNEL_ASSERT => {
+            crate::kassert!(args[0] == 0, "sys_kernel_assert({})", args[0]);
+            0
+        }
+        // Unlike a failed kassert!, this goes through the panic handler.
+        #[cfg(feature = "fault-injection")]
+        SYSCALL_KERNEL_PANIC => panic!("sys_kernel_panic"),
+        // The panic handler must still get its report out, and shut down.
+        #[cfg(feature = "fault-injection")]
+        SYSCALL_KERNEL_PANIC_LOCKED => {
+            let _task_mgr = TASK_MANAGER.lock();
+            panic!("sys_kernel_panic_locked");
+        }
+        // The kernel stack is only checked on a trap, so this one returns.
+        #[cfg(feature = "fault-injection")]
+        SYSCALL_CLOBBER_STACK_GUARD => {
+            crate::task::clobber_guard(current_task());
+            0
+        }
+        #[cfg(not(feature = "fault-injection"))]
+        SYSCALL_KERNEL_FAULT
+        | SYSCALL_KERNEL_ASSERT
+        | SYSCALL_KERNEL_PANIC
+        | SYSCALL_KERNEL_PANIC_LOCKED
+        | SYSCALL_CLOBBER_STACK_GUARD => -1,
+        // Likewise the canary is only checked on a switch.
+        #[cfg(all(feature = "context-canary", feature = "fault-injection"))]
+        SYSCALL_CLOBBER_CONTEXT_CANARY => {
+            TASK_MANAGER.lock().clobber_context_canary();
+            0
+        }
+        #[cfg(not(all(feature = "context-canary", feature = "fault-injection")))]
+        SYSCALL_CLOBBER_CONTEXT_CANARY => -1,
+        SYSCALL_GET_TASK_COUNT => TASK_MANAGER.lock().num_app() as isize,
+        SYSCALL_TASK_NAME => {
+            let (buffer_ptr, buffer_size) = (args[1], args[2]);
+            let task_mgr = TASK_MANAGER.lock();
+            if !task_mgr.check_user_ptr(buffer_ptr, buffer_size) {
+                return -1;
+            }
+            let name = match task_mgr.task_name(args[0]) {
+                Some(name) => name.as_bytes(),
+                None => return -1,
+            };
+            if name.len() > buffer_size {
+                return -1;
+            }
+            let buffer = unsafe { core::slice::from_raw_parts_mut(buffer_ptr as *mut u8, name.len()) };
+            buffer.copy_from_slice(name);
+            name.len() as isize
+        }
+        SYSCALL_SYSCALL_NAME => {
+            let (buffer_ptr, buffer_size) = (args[1], args[2]);
+            let name = match name(args[0]) {
+                "unknown" => return -1,
+                name => name.as_bytes(),
+            };
+            if name.len() > buffer_size || !check_user_ptr(buffer_ptr, buffer_size) {
+                return -1;
+            }
+            let buffer = unsafe { core::slice::from_raw_parts_mut(buffer_ptr as *mut u8, name.len()) };
+            buffer.copy_from_slice(name);
+            name.len() as isize
+        }
+        SYSCALL_DUMP_TRACE => {
+            let (entries_ptr, max) = (args[0] as *mut TraceEntry, args[1]);
+            let fits = max
+                .checked_mul(size_of::<TraceEntry>())
+                .map_or(false, |len| check_user_ptr(entries_ptr as usize, len));
+            if !fits {
+                return -1;
+            }
+            let entries = unsafe { core::slice::from_raw_parts_mut(entries_ptr, max) };
+            copy_trace(entries) as isize
+        }
+        SYSCALL_GET_CLOCK_FREQ => time::CLOCK_FREQ as isize,
+        SYSCALL_GET_TICKS => time::get_time() as isize,
+        SYSCALL_UPTIME => time::uptime_ms() as isize,
+        SYSCALL_WAIT_EVENT => if wait_event_and_run_next(args[0]) { 0 } else { -1 },
+        SYSCALL_SIGNAL_EVENT => match TASK_MANAGER.lock().signal_event(args[0]) {
+            Some(woken) => woken as isize,
+            None => -1,
+        },
+        SYSCALL_SEND => if TASK_MANAGER.lock().send(args[0], args[1]) { 0 } else { -1 },
+        SYSCALL_RECV => {
+            let msg_ptr = args[0] as *mut usize;
+            if !check_user_ptr(msg_ptr as usize, size_of::<usize>()) {
+                return -1;
+            }
+            let msg = recv_or_run_next();
+            unsafe { *msg_ptr = msg };
+            0
+        }
+        SYSCALL_SET_AFFINITY => {
+            // A task must be allowed somewhere.
+            if args[0] == 0 {
+                return -1;
+            }
+            set_current_affinity(args[0]);
+            0
+        }
+        SYSCALL_TRAP_STATS => {
+            let trap_stats_ptr = args[0] as *mut TrapStats;
+            let task_mgr = TASK_MANAGER.lock();
+            if !task_mgr.check_user_ptr(trap_stats_ptr as usize, size_of::<TrapStats>()) {
+                return -1;
+            }
+            let mut stats = trap_stats();
+            stats.task_timer_interrupts = task_mgr.current_stat().timer_interrupts;
+            unsafe { *trap_stats_ptr = stats };
+            0
+        }
+        SYSCALL_YIELD_TO => {
+            // Yielding to oneself, the running task, yields as usual.
+            record_voluntary_switch();
+            if yield_to(args[0]) { 0 } else { -1 }
+        }
+        SYSCALL_KILL => {
+            let task_id = args[0];
+            // Killing oneself is exiting, which doesn't return.
+            if task_id == current_task() {
+                exit_and_run_next(-1);
+            }
+            if TASK_MANAGER.lock().kill(task_id, -1) { 0 } else { -1 }
+        }
+        SYSCALL_GETRANDOM => {
+            let (buf, len) = (args[0], args[1]);
+            let mut task_mgr = TASK_MANAGER.lock();
+            if !task_mgr.check_user_ptr(buf, len) {
+                return -1;
+            }
+            let buffer = unsafe { core::slice::from_raw_parts_mut(buf as *mut u8, len) };
+            task_mgr.with_current_tcb_mut(|tcb| tcb.rng.fill_bytes(buffer));
+            len as isize
+        }
+        SYSCALL_LIST_TASKS => {
+            let (briefs_ptr, max) = (args[0] as *mut TaskBrief, args[1]);
+            let task_mgr = TASK_MANAGER.lock();
+            let fits = max
+                .checked_mul(size_of::<TaskBrief>())
+                .map_or(false, |len| task_mgr.check_user_ptr(briefs_ptr as usize, len));
+            if !fits {
+                return -1;
+            }
+            let briefs = unsafe { core::slice::from_raw_parts_mut(briefs_ptr, max) };
+            let tasks = (0..).map_while(|id| Some((id, task_mgr.tcb(id)?, task_mgr.stat(id)?)));
+            let mut count = 0;
+            for (brief, (id, tcb, stat)) in briefs.iter_mut().zip(tasks) {
+                *brief = TaskBrief {
+                    id,
+                    status: tcb.status,
+                    cpu_clocks: clocks_to_usize(stat.cpu_clocks_so_far()),
+                    priority: tcb.priority,
+                };
+                count += 1;
+            }
+            count
+        }
+        SYSCALL_IDLE_TIME => idle_clocks() as isize,
+        SYSCALL_SET_ALARM => {
+            set_current_alarm(args[0].saturating_mul(time::CLOCKS_PER_MILLI_SEC));
+            0
+        }
+        SYSCALL_POLL_ALARM => poll_current_alarm() as isize,
+        SYSCALL_SET_ALARM_HANDLER => {
+            let handler = args[0];
+            if handler != 0 && !check_user_ptr(handler, 2) {
+                return -1;
+            }
+            set_current_alarm_handler(handler);
+            0
+        }
+        // `trap_handler` puts back the interrupted context, see
+        // `take_alarm_context`.
+        SYSCALL_SIGRETURN => if in_alarm_handler() { 0 } else { -1 },
+        // Never in a slot other than the app's own, see `sys_spawn_app`: -1
+        // while that one is in use, even if others are free.
+        SYSCALL_SPAWN => match TASK_MANAGER.lock().spawn(args[0]) {
+            Some(task_id) => task_id as isize,
+            None => -1,
+        },
+        SYSCALL_WAITTID => {
+            let exit_code_ptr = args[1] as *mut i32;
+            if !check_user_ptr(exit_code_ptr as usize, size_of::<i32>()) {
+                return -1;
+            }
+            // Blocks for a child of the caller, see `sys_spawn`.
+            match waittid_or_run_next(args[0]) {
+                Some(exit_code) => {
+                    unsafe { *exit_code_ptr = exit_code };
+                    0
+                }
+                None => -1,
+            }
+        }
+        unknown => {
+            println!("[kernel] Unsupported syscall `{}` in application, kernel killed it.", unknown);
+            exit_and_run_next(-1);
+            -1
+        }
+    }
+}
+
+/// Read a line from the console into `buffer` with echo and simple line
+/// editing, yielding while no input is available.
+///
+/// Reading stops after a newline (included in the result) or when `buffer`
+/// is full. Returns the number of bytes read.
+fn read_line(buffer: &mut [u8]) -> usize {
+    const BACKSPACE: u8 = 0x08;
+    const DELETE: u8 = 0x7f;
+
+    let mut len = 0;
+    while len < buffer.len() {
+        let c = match console::getchar() {
+            Some(c) => c,
+            None => {
+                run_next_task();
+                continue;
+            }
+        };
+        match c {
+            BACKSPACE | DELETE => {
+                if len > 0 {
+                    len -= 1;
+                    print!("\x08 \x08");
+                }
+            }
+            b'\r' | b'\n' => {
+                console::putchar(b'\n');
+                buffer[len] = b'\n';
+                len += 1;
+                break;
+            }
+            c => {
+                console::putchar(c);
+                buffer[len] = c;
+                len += 1;
+            }
+        }
     }
+    len
 }
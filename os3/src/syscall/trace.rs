@@ -0,0 +1,93 @@
+use crate::println;
+use crate::sync::InterruptSafeMutex;
+use crate::time;
+
+/// Syscalls remembered, the oldest being overwritten first.
+pub const TRACE_CAPACITY: usize = 256;
+/// Syscalls printed by the panic handler, see `print_trace`.
+const PANIC_TRACE_LEN: usize = 16;
+
+/// A syscall as it was entered, see `SYSCALL_DUMP_TRACE`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TraceEntry {
+    pub task_id: usize,
+    pub syscall_id: usize,
+    pub arg0: usize,
+    /// Clock count read with `get_time64`.
+    pub time: u64,
+}
+
+impl TraceEntry {
+    const EMPTY: Self = Self { task_id: 0, syscall_id: 0, arg0: 0, time: 0 };
+}
+
+/// The last `TRACE_CAPACITY` syscalls made by any task, oldest first.
+struct Trace {
+    entries: [TraceEntry; TRACE_CAPACITY],
+    head: usize,
+    len: usize,
+}
+
+impl Trace {
+    const fn new() -> Self {
+        Self { entries: [TraceEntry::EMPTY; TRACE_CAPACITY], head: 0, len: 0 }
+    }
+
+    fn push(&mut self, entry: TraceEntry) {
+        self.entries[(self.head + self.len) % TRACE_CAPACITY] = entry;
+        if self.len == TRACE_CAPACITY {
+            self.head = (self.head + 1) % TRACE_CAPACITY;
+        } else {
+            self.len += 1;
+        }
+    }
+
+    /// The last `n` entries, oldest first.
+    fn recent(&self, n: usize) -> impl Iterator<Item = &TraceEntry> {
+        let skip = self.len.saturating_sub(n);
+        (skip..self.len).map(move |i| &self.entries[(self.head + i) % TRACE_CAPACITY])
+    }
+}
+
+static TRACE: InterruptSafeMutex<Trace> = InterruptSafeMutex::new(Trace::new());
+
+/// Remember that `task_id` entered syscall `syscall_id`.
+pub fn record_trace(task_id: usize, syscall_id: usize, arg0: usize) {
+    TRACE.lock().push(TraceEntry { task_id, syscall_id, arg0, time: time::get_time64() });
+}
+
+/// Copy the last `out.len()` syscalls at most into `out`, oldest first,
+/// returning how many were copied.
+pub fn copy_trace(out: &mut [TraceEntry]) -> usize {
+    let trace = TRACE.lock();
+    let max = out.len();
+    let mut count = 0;
+    for (slot, entry) in out.iter_mut().zip(trace.recent(max)) {
+        *slot = *entry;
+        count += 1;
+    }
+    count
+}
+
+/// Print the last syscalls made, for the panic handler. The lock is only
+/// tried, as the panic may have happened with it held.
+pub fn print_trace() {
+    let trace = match TRACE.try_lock() {
+        Some(trace) => trace,
+        None => {
+            println!("syscall trace unavailable: lock held");
+            return;
+        }
+    };
+    println!("last syscalls, oldest first:");
+    for entry in trace.recent(PANIC_TRACE_LEN) {
+        println!(
+            "    {:>16} task {:>2} {:<18} arg0 = {:#x}",
+            entry.time,
+            entry.task_id,
+            super::name(entry.syscall_id),
+            entry.arg0
+        );
+    }
+}
@@ -1,33 +1,125 @@
+mod event;
+mod fd_table;
+mod layout;
+mod mailbox;
+mod run_queue;
+mod sched;
 mod stack;
 
 use lazy_static::lazy_static;
+#[cfg(not(test))]
 use core::arch::global_asm;
+#[cfg(not(test))]
 use core::arch::asm;
-use spin::Mutex;
+use core::sync::atomic::{self, AtomicUsize};
+use riscv::register::sstatus::{self, FS};
 
-use stack::{ KernelStack, UserStack };
+use event::Events;
+pub use event::NUM_EVENTS;
+use fd_table::FdTable;
+pub use fd_table::FdTarget;
+pub use layout::{check_kernel_end, APP_BASE_ADDR, BOOT_STACK_SIZE, KERNEL_BASE_ADDR, MAX_APP_SIZE, MEMORY_END};
+use mailbox::Mailbox;
+use run_queue::RunQueue;
+use sched::{ActiveScheduler, Scheduler};
+use stack::{ KernelStack, UserStack, KERNEL_STACK_SIZE, USER_STACK_SIZE };
 use crate::trap::TrapContext;
+use crate::console;
 use crate::sbi;
-use crate::println;
+use crate::sync::{InterruptSafeMutex, InterruptSafeMutexGuard};
+use crate::{kassert, log_debug, log_info, log_warn};
 use crate::trap::__restore;
 use crate::time;
-use crate::syscall::MAX_SYSCALL_NUM;
+use crate::util;
+use crate::rand::XorShift64;
+use crate::syscall::{self, MAX_SYSCALL_NUM};
 
-const MAX_TASK_NUM: usize = 32;
+/// The most apps the kernel image may contain, a hard cap: every per-task
+/// array is this large, and a kernel linked with more apps refuses to boot.
+/// Set at build time with the `MAX_TASK_NUM` environment variable, e.g.
+/// `make run MAX_TASK_NUM=16`, up to 64 and within the memory the stacks
+/// may take, see `layout.rs`.
+pub const MAX_TASK_NUM: usize = match option_env!("MAX_TASK_NUM") {
+    Some(num) => time::parse_usize(num),
+    None => 32,
+};
+const _: () = assert!(MAX_TASK_NUM > 0, "MAX_TASK_NUM must be positive");
+
+pub const DEFAULT_PRIORITY: usize = 16;
+pub const MIN_PRIORITY: usize = 2;
+
+/// Strides are compared with wrapping arithmetic. Since a pass is at most
+/// `BIG_STRIDE / MIN_PRIORITY`, the strides of any two tasks stay within half
+/// of the `usize` range of each other, which keeps the comparison correct
+/// after they overflow.
+const BIG_STRIDE: usize = usize::MAX;
+
+/// Lottery tickets a task holds until it sets its own.
+pub const DEFAULT_TICKETS: usize = 100;
+/// The most tickets a task may hold, so that those of every task add up in
+/// a u64 without overflowing.
+pub const MAX_TICKETS: usize = u32::MAX as usize;
+
+/// With `fault-injection`, the app whose image `load_task` corrupts right
+/// after copying it, to see the check that follows catch it.
+#[cfg(feature = "fault-injection")]
+const CORRUPTED_APP: &str = "corrupt_load";
+
+/// Apps allowed to make privileged syscalls, such as `sys_reboot`. Given by
+/// name, since ids depend on which apps are linked, and set at build time
+/// with the comma-separated `PRIVILEGED_APPS` environment variable, e.g.
+/// `make run PRIVILEGED_APPS=ch3_reboot`. None by default.
+const PRIVILEGED_APPS: &str = match option_env!("PRIVILEGED_APPS") {
+    Some(apps) => apps,
+    None => "",
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedPolicy {
+    RoundRobin,
+    Stride,
+    Mlfq,
+    Edf,
+    Lottery,
+}
+
+/// Whether the timer preempts tasks. Without it a task that never gives up
+/// the CPU starves all the others.
+pub const PREEMPTIVE: bool = cfg!(not(feature = "cooperative"));
 
-const APP_BASE_ADDR: *mut u8 = 0x80400000 as *mut u8;
-const MAX_APP_SIZE: usize = 0x20000;
+/// Picked with the `stride`, `mlfq`, `edf` or `lottery` feature, round-robin
+/// without any.
+pub const SCHED_POLICY: SchedPolicy = <ActiveScheduler as Scheduler>::POLICY;
 
+/// The top of each app's slot is left for its heap, grown with `sys_sbrk`.
+const USER_HEAP_SIZE: usize = 0x4000;
+/// What is left of the slot for the image. The app's .bss, which isn't part
+/// of the image, must fit as well, which the user build checks when it
+/// links the apps.
+const MAX_IMAGE_SIZE: usize = MAX_APP_SIZE - USER_HEAP_SIZE;
+const _: () = assert!(MAX_APP_SIZE > USER_HEAP_SIZE, "MAX_APP_SIZE leaves no room for the image");
+
+#[cfg(not(test))]
 global_asm!(include_str!("link_app.S"));
 extern "C" {
     static _num_app: usize;
+    /// Names of the apps, as consecutive NUL-terminated strings.
+    static _app_names: u8;
 }
 
+#[cfg(not(test))]
 global_asm!(include_str!("task/switch.S"));
 extern "C" {
     fn __switch(current_cx: *mut TaskContext, next_cx: *mut TaskContext);
 }
 
+#[cfg(not(test))]
+global_asm!(include_str!("task/fp.S"));
+extern "C" {
+    fn __fp_save(cx: *mut FpContext);
+    fn __fp_restore(cx: *const FpContext);
+}
+
 static KERNEL_STACK: [KernelStack ; MAX_TASK_NUM]= {
     const KERNEL_STACK: KernelStack = KernelStack::new();
     [KERNEL_STACK; MAX_TASK_NUM]
@@ -37,8 +129,30 @@ static USER_STACK: [UserStack; MAX_TASK_NUM] = {
     [USER_STACK; MAX_TASK_NUM]
 };
 
+/// `TaskManager::current_task` readable without taking the lock, so that it
+/// can be used while the lock is held. `usize::MAX` until a task has run.
+static CURRENT_TASK_ID: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Clocks spent in `idle` with no task ready to run.
+static IDLE_CLOCKS: AtomicUsize = AtomicUsize::new(0);
+
+/// When the running task's time slice ends, see `start_time_slice`.
+static SLICE_END: AtomicUsize = AtomicUsize::new(0);
+
+/// How far from the deadline asked for the timer may go off, if it's
+/// already programmed that close. Reprogramming it is an SBI call, which
+/// a yield loop would otherwise make on every switch. A time slice may
+/// end up to this much early.
+const TIMER_SLACK: usize = time::time_slice_clocks() / 10;
+
+/// The deadline the timer was last programmed with.
+static TIMER_DEADLINE: AtomicUsize = AtomicUsize::new(0);
+/// Deadlines programmed through SBI, and those left to the one already set.
+static TIMERS_SET: AtomicUsize = AtomicUsize::new(0);
+static TIMERS_COALESCED: AtomicUsize = AtomicUsize::new(0);
+
 lazy_static! {
-    pub static ref TASK_MANAGER: Mutex<TaskManager> = Mutex::new(unsafe { TaskManager::new() });
+    pub static ref TASK_MANAGER: InterruptSafeMutex<TaskManager> = InterruptSafeMutex::new(unsafe { TaskManager::new() });
 }
 
 // #[repr(C)]
@@ -48,49 +162,152 @@ pub enum TaskStatus {
     UnInit = 0,
     Ready = 1,
     Running = 2,
+    /// Reaped by `sys_waittid`, nothing is left of the task.
     Exited = 3,
+    Blocked = 4,
+    /// Exited, but the exit code and stats are kept until reaped.
+    Zombie = 5,
 }
 
 
+/// Whether `move_to_next_task` checks the canaries of the contexts it
+/// switches between, see `TaskContext::canary`.
+const CHECK_CONTEXT_CANARY: bool = cfg!(feature = "context-canary");
+const CONTEXT_CANARY: usize = 0xc0de_5a5a;
+
 #[derive(Debug, Clone, Default)]
 #[repr(C)]
 pub struct TaskContext {
     ra: usize,
     sp: usize,
     s0_11: [usize; 12],
+    /// `CONTEXT_CANARY` once initialized. It comes after everything
+    /// `__switch` saves, so the offsets in switch.S are unaffected and
+    /// nothing legitimate ever writes it.
+    canary: usize,
 }
 
+/// The user's floating-point registers. The kernel doesn't use them, so
+/// they only need to be switched along with the task.
+#[derive(Debug, Clone, Default)]
+#[repr(C)]
+pub struct FpContext {
+    f: [u64; 32],
+    fcsr: usize,
+}
+
+/// Clock counts and timestamps are u64 whatever the pointer width, as a
+/// 32-bit count wraps after about 343 s.
 #[derive(Debug, Clone)]
 pub struct TaskStat {
-    pub cpu_clocks: usize,
-    pub first_scheduled: Option<usize>,
-    pub last_scheduled: Option<usize>,
+    pub cpu_clocks: u64,
+    /// Part of `cpu_clocks` spent in the kernel on behalf of the task.
+    pub kernel_clocks: u64,
+    /// Part of `cpu_clocks` spent in user mode.
+    pub user_clocks: u64,
+    /// How many times the task has been scheduled.
+    pub schedule_count: usize,
+    /// Timer interrupts taken while the task was running, wrapping around.
+    pub timer_interrupts: usize,
+    /// Times the task gave up the CPU with `sys_yield` or `sys_yield_to`.
+    pub voluntary_switches: usize,
+    /// Times the timer preempted the task.
+    pub involuntary_switches: usize,
+    /// When the task was loaded, read with `get_time64` like the other
+    /// timestamps.
+    pub created_at: u64,
+    pub first_scheduled: Option<u64>,
+    pub last_scheduled: Option<u64>,
+    /// Whether the interval that began at `last_scheduled` is still being
+    /// counted. It ends once, when the task is switched away from or
+    /// before the CPU idles, whichever comes first.
+    on_cpu: bool,
+    /// When the task last crossed the user/kernel boundary or was scheduled.
+    mode_switched: u64,
     pub syscall_times: [u32; MAX_SYSCALL_NUM],
 }
 
 impl TaskStat {
     pub fn record_schedule_begin(&mut self) {
+        self.schedule_count += 1;
         if self.last_scheduled.is_none() {
-            self.first_scheduled = Some(time::get_time());
+            self.first_scheduled = Some(time::get_time64());
             self.last_scheduled = self.first_scheduled;
         } else {
-            self.last_scheduled = Some(time::get_time());
+            self.last_scheduled = Some(time::get_time64());
         }
+        // Tasks are always switched to in the kernel.
+        self.mode_switched = self.last_scheduled.unwrap();
+        self.on_cpu = true;
     }
 
+    /// End the interval begun by `record_schedule_begin`, if it hasn't
+    /// ended already.
     pub fn record_schedule_end(&mut self) {
+        if !self.on_cpu {
+            return;
+        }
+        self.on_cpu = false;
         if let Some(last_scheduled) = self.last_scheduled {
-            self.cpu_clocks += time::get_time().checked_sub(last_scheduled).expect("time goes backward");
+            self.cpu_clocks += time::clocks_since64(last_scheduled);
+            self.kernel_clocks += self.clocks_since_mode_switch();
         }
     }
 
+    pub fn record_trap_enter(&mut self) {
+        self.user_clocks += self.clocks_since_mode_switch();
+    }
+
+    pub fn record_trap_return(&mut self) {
+        self.kernel_clocks += self.clocks_since_mode_switch();
+    }
+
+    /// `cpu_clocks` in milliseconds, the same whatever `CLOCK_FREQ` is.
+    pub fn cpu_time_ms(&self) -> u64 {
+        time::clocks_to_ms(self.cpu_clocks)
+    }
+
+    pub fn cpu_time_us(&self) -> u64 {
+        time::clocks_to_us(self.cpu_clocks)
+    }
+
+    /// `cpu_clocks` including the time the task has been running for, if it
+    /// is running.
+    pub fn cpu_clocks_so_far(&self) -> u64 {
+        match self.last_scheduled {
+            Some(last_scheduled) if self.on_cpu => self.cpu_clocks + time::clocks_since64(last_scheduled),
+            _ => self.cpu_clocks,
+        }
+    }
+
+    fn clocks_since_mode_switch(&mut self) -> u64 {
+        let now = time::get_time64();
+        let clocks = time::clocks_between64(self.mode_switched, now);
+        self.mode_switched = now;
+        clocks
+    }
+
+    /// Count a syscall. Out-of-range syscall ids are not counted.
     pub fn record_syscall(&mut self, syscall: usize) {
-        self.syscall_times[syscall] += 1;
+        if let Some(times) = self.syscall_times.get_mut(syscall) {
+            *times = times.saturating_add(1);
+        }
     }
 
-    pub fn real_time(&self) -> usize {
+    pub fn total_syscalls(&self) -> u64 {
+        self.syscall_times.iter().map(|&times| times as u64).sum()
+    }
+
+    /// Clocks from being loaded to first running, if it has run.
+    pub fn dispatch_latency(&self) -> Option<u64> {
+        self.first_scheduled
+            .map(|first_scheduled| time::clocks_between64(self.created_at, first_scheduled))
+    }
+
+    /// Clocks since the task first ran, 0 if it hasn't.
+    pub fn real_time(&self) -> u64 {
         if let Some(first_scheduled) = self.first_scheduled {
-            time::get_time().checked_sub(first_scheduled).expect("time goes backward")
+            time::clocks_since64(first_scheduled)
         } else {
             0
         }
@@ -101,120 +318,688 @@ impl Default for TaskStat {
     fn default() -> Self {
         Self {
             cpu_clocks: 0, 
+            kernel_clocks: 0,
+            user_clocks: 0,
+            schedule_count: 0,
+            timer_interrupts: 0,
+            voluntary_switches: 0,
+            involuntary_switches: 0,
+            created_at: 0,
             first_scheduled: None,
             last_scheduled: None,
+            on_cpu: false,
+            mode_switched: 0,
             syscall_times: [0; MAX_SYSCALL_NUM],
         }
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct TaskControlBlock {
     pub status: TaskStatus,
+    pub priority: usize,
+    pub stride: usize,
+    pub pass: usize,
+    /// When a sleeping task should become ready again.
+    pub wake_at: Option<usize>,
+    pub exit_code: i32,
+    /// MLFQ level, 0 being the highest. Always 0 under other policies.
+    pub level: usize,
+    /// Timer ticks taken from the quantum of the current level.
+    ticks_used: usize,
+    /// The time, in clocks, by which the task should be done under EDF.
+    pub deadline: Option<usize>,
+    /// Whether the miss of `deadline` has been reported.
+    deadline_missed: bool,
+    /// When the task last became Ready, which orders equally preferable
+    /// tasks first come, first served.
+    ready_since: usize,
+    /// The end of the task's heap, which starts at `heap_bottom(task_id)`.
+    pub brk: usize,
+    /// CPU clocks the task may use before it is killed, if limited.
+    pub cpu_limit: Option<usize>,
+    /// Lottery tickets, which its chance of being picked is proportional to.
+    pub tickets: usize,
+    /// Period of the task's alarm in clocks, if it has one.
+    pub alarm_interval: Option<usize>,
+    /// When the alarm fires next.
+    alarm_at: usize,
+    /// Alarms fired since the task last polled.
+    pub alarms_fired: usize,
+    /// User function entered with the number of alarms fired, instead of
+    /// waiting for the task to poll.
+    pub alarm_handler: Option<usize>,
+    /// What the alarm handler interrupted, while it runs. `sys_sigreturn`
+    /// puts it back.
+    alarm_cx: Option<TrapContext>,
+    /// Bit `i` set if the task may run on hart `i`. Only recorded: there is
+    /// a single hart, which runs every task whatever the mask.
+    pub affinity: usize,
+    /// Messages sent to the task, see `sys_send`.
+    mailbox: Mailbox,
+    /// Whether the task is blocked until a message arrives.
+    awaiting_mail: bool,
+    /// The task that spawned it, if any.
+    pub parent: Option<usize>,
+    /// The child whose exit the task is blocked on in `sys_waittid`.
+    waiting_for: Option<usize>,
+    fds: FdTable,
+    /// Whether the app is one of `PRIVILEGED_APPS`.
+    pub privileged: bool,
+    /// The task's own stream for `sys_getrandom`.
+    pub rng: XorShift64,
     cx: TaskContext,
+    fp: FpContext,
+}
+
+impl TaskControlBlock {
+    pub fn set_priority(&mut self, priority: usize) {
+        self.priority = priority;
+        self.pass = BIG_STRIDE / priority;
+    }
+
+    /// Set the deadline `clocks` from now.
+    pub fn set_deadline(&mut self, clocks: usize) {
+        self.deadline = Some(time::get_time().saturating_add(clocks));
+        self.deadline_missed = false;
+    }
+
+    /// Fire the alarm every `interval` clocks from now, or stop it.
+    pub fn set_alarm(&mut self, interval: Option<usize>) {
+        self.alarm_interval = interval;
+        self.alarm_at = time::get_time().saturating_add(interval.unwrap_or(0));
+        self.alarms_fired = 0;
+    }
+
+    /// Count the alarms due by `now`. Several may be, if nobody looked for
+    /// a while.
+    fn update_alarm(&mut self, now: usize) {
+        let interval = match self.alarm_interval {
+            Some(interval) if now >= self.alarm_at => interval,
+            _ => return,
+        };
+        let fired = (now - self.alarm_at) / interval + 1;
+        self.alarms_fired += fired;
+        self.alarm_at = self.alarm_at.saturating_add(fired * interval);
+    }
+
+    /// Take the number of alarms fired since the last poll.
+    pub fn poll_alarm(&mut self) -> usize {
+        self.update_alarm(time::get_time());
+        core::mem::take(&mut self.alarms_fired)
+    }
+}
+
+impl Default for TaskControlBlock {
+    fn default() -> Self {
+        Self {
+            status: TaskStatus::default(),
+            priority: DEFAULT_PRIORITY,
+            stride: 0,
+            pass: BIG_STRIDE / DEFAULT_PRIORITY,
+            wake_at: None,
+            exit_code: 0,
+            level: 0,
+            ticks_used: 0,
+            deadline: None,
+            deadline_missed: false,
+            ready_since: 0,
+            brk: 0,
+            cpu_limit: None,
+            tickets: DEFAULT_TICKETS,
+            alarm_interval: None,
+            alarm_at: 0,
+            alarms_fired: 0,
+            alarm_handler: None,
+            alarm_cx: None,
+            affinity: usize::MAX,
+            mailbox: Mailbox::default(),
+            awaiting_mail: false,
+            parent: None,
+            waiting_for: None,
+            fds: FdTable::default(),
+            privileged: false,
+            rng: XorShift64::default(),
+            cx: TaskContext::default(),
+            fp: FpContext::default(),
+        }
+    }
 }
 
 pub struct TaskManager {
     app_starts: &'static [usize],
+    app_names: [&'static str; MAX_TASK_NUM],
     num_app: usize,
     current_task: usize,
     tcbs: [TaskControlBlock; MAX_TASK_NUM],
     stats: [TaskStat; MAX_TASK_NUM],
+    /// Exactly the tasks whose status is Ready.
+    ready_queue: RunQueue,
+    scheduler: ActiveScheduler,
+    events: Events,
 }
 
 impl TaskManager {
     pub unsafe fn new() -> Self {
         let ptr = &_num_app as *const usize;
         let num_app = *ptr;
+        assert!(
+            num_app <= MAX_TASK_NUM,
+            "the kernel was linked with {} apps, but only supports MAX_TASK_NUM ({}); \
+            link fewer apps or build with a higher MAX_TASK_NUM",
+            num_app, MAX_TASK_NUM
+        );
         let app_starts = {
             let table = ptr.add(1);
             // The last one is a marker for the end.
             core::slice::from_raw_parts(table, num_app + 1)
         };
 
-        let mut tcbs: [TaskControlBlock; MAX_TASK_NUM] = Default::default();
-        let stats: [TaskStat; MAX_TASK_NUM] = Default::default();
+        let mut app_names = [""; MAX_TASK_NUM];
+        let mut name_ptr = &_app_names as *const u8;
+        for name in app_names.iter_mut().take(num_app) {
+            let len = (0..).find(|&i| *name_ptr.add(i) == 0).unwrap();
+            *name = core::str::from_utf8(core::slice::from_raw_parts(name_ptr, len))
+                .expect("app name isn't UTF-8");
+            name_ptr = name_ptr.add(len + 1);
+        }
 
-        tcbs.iter_mut()
-            .enumerate()
-            .take(num_app)
-            .for_each(|(i, tcb)| {
-                tcb.cx.sp = KERNEL_STACK[i].get_sp() as usize;
-                tcb.cx.ra = start_task as usize;
-            });
+        let mut task_mgr = Self::with_apps(app_starts, app_names);
+        for i in 0..num_app {
+            task_mgr.init_task(i);
+            task_mgr.load_task(i);
+        }
+        task_mgr
+    }
 
-        let mut task_mgr = Self {
+    /// A task manager for the apps starting at `app_starts`, the last entry
+    /// being where the last one ends, none of them loaded yet.
+    fn with_apps(app_starts: &'static [usize], app_names: [&'static str; MAX_TASK_NUM]) -> Self {
+        let tcbs: [TaskControlBlock; MAX_TASK_NUM] = [(); MAX_TASK_NUM].map(|_| Default::default());
+        let stats: [TaskStat; MAX_TASK_NUM] = [(); MAX_TASK_NUM].map(|_| Default::default());
+        Self {
             app_starts,
-            num_app,
+            app_names,
+            num_app: app_starts.len() - 1,
             current_task: 0,
             tcbs,
             stats,
-        };
-
-        for i in 0..num_app {
-            task_mgr.load_task(i);
+            ready_queue: RunQueue::new(),
+            scheduler: ActiveScheduler::new(),
+            events: Events::new(),
         }
+    }
 
-        task_mgr
+    /// Give `task_id` a fresh TCB and stats, set to start from the beginning
+    /// of its kernel stack.
+    fn init_task(&mut self, task_id: usize) {
+        self.tcbs[task_id] = TaskControlBlock::default();
+        self.stats[task_id] = TaskStat::default();
+        KERNEL_STACK[task_id].init_guard();
+        let cx = &mut self.tcbs[task_id].cx;
+        cx.sp = KERNEL_STACK[task_id].get_sp();
+        cx.ra = start_task as usize;
+        cx.canary = CONTEXT_CANARY;
+    }
+
+    /// Run app `app_id` again, as task `app_id` since each app has its own
+    /// slot. Its last run must have been reaped. Returns the task id, or
+    /// `None` if the slot is in use or there is no such app. No other slot
+    /// is tried: the app is linked to run at the address of its own.
+    pub fn spawn(&mut self, app_id: usize) -> Option<usize> {
+        let tcb = self.tcb(app_id)?;
+        if !matches!(tcb.status, TaskStatus::UnInit | TaskStatus::Exited) {
+            return None;
+        }
+        self.init_task(app_id);
+        // The slot isn't in use, nor are its stacks.
+        unsafe { self.load_task(app_id) };
+        self.tcbs[app_id].parent = Some(self.current_task);
+        Some(app_id)
     }
 
     pub unsafe fn load_task(&mut self, task_id: usize) {
         let task_start = self.app_starts[task_id];
         let task_end = self.app_starts[task_id + 1];
         let task_size = task_end.saturating_sub(task_start);
+        if task_size > MAX_IMAGE_SIZE {
+            panic!(
+                "task `{}` is {:#x} bytes, larger than MAX_IMAGE_SIZE ({:#x})",
+                self.app_names[task_id], task_size, MAX_IMAGE_SIZE
+            );
+        }
 
         let load_to = get_task_base(task_id);
-        println!("task `{task_id}` loaded at `0x{:x}`", load_to as usize);
-        core::ptr::copy_nonoverlapping(task_start as *const u8, load_to, task_size);
+        // Whatever a previous run left behind, in its .bss, heap or stack,
+        // must not be visible to the next one.
+        core::ptr::write_bytes(load_to, 0, MAX_APP_SIZE);
+        USER_STACK[task_id].clear();
+        log_info!("task `{}` loaded at `0x{:x}`", self.app_names[task_id], load_to as usize);
+        let image = core::slice::from_raw_parts(task_start as *const u8, task_size);
+        let crc = util::crc32(image);
+        core::ptr::copy_nonoverlapping(image.as_ptr(), load_to, task_size);
+        if console::LogLevel::Debug as usize <= console::LOG_LEVEL as usize {
+            log_debug!("first bytes of task `{}`:", self.app_names[task_id]);
+            console::hexdump(load_to, task_size.min(64));
+        }
+
+        #[cfg(feature = "fault-injection")]
+        if self.app_names[task_id] == CORRUPTED_APP && task_size > 0 {
+            *load_to ^= 1;
+        }
 
+        #[cfg(not(test))]
         asm!("fence.i");
-        self.tcbs[task_id].status = TaskStatus::Ready;
+        let loaded = core::slice::from_raw_parts(load_to, task_size);
+        let loaded_crc = util::crc32(loaded);
+        if loaded_crc != crc {
+            panic!(
+                "task `{}` is corrupted after loading, CRC-32 {:#010x} instead of {:#010x}",
+                self.app_names[task_id], loaded_crc, crc
+            );
+        }
+        self.tcbs[task_id].brk = heap_bottom(task_id);
+        self.tcbs[task_id].privileged = PRIVILEGED_APPS.split(',').any(|app| app == self.app_names[task_id]);
+        // Tasks loaded in the same clock would share a seed without the id.
+        self.tcbs[task_id].rng = XorShift64::new(time::get_time64() ^ ((task_id as u64) << 48));
+        let now = time::get_time();
+        self.stats[task_id].created_at = time::get_time64();
+        self.make_ready(task_id, now);
+    }
+
+    /// Whether `[ptr, ptr + len)` lies entirely within the current task's
+    /// image or its user stack, so that the kernel may access it on the
+    /// task's behalf.
+    pub fn check_user_ptr(&self, ptr: usize, len: usize) -> bool {
+        let end = match ptr.checked_add(len) {
+            Some(end) => end,
+            None => return false,
+        };
+        let task_id = self.current_task;
+        let image_start = get_task_base(task_id) as usize;
+        let stack_start = USER_STACK[task_id].get_bottom() as usize;
+        let regions = [
+            (image_start, image_start + MAX_APP_SIZE),
+            (stack_start, USER_STACK[task_id].get_sp() as usize),
+        ];
+        regions.iter().any(|&(start, limit)| start <= ptr && end <= limit)
+    }
+
+    /// Move the current task's heap end by `increment` bytes. Returns the
+    /// old end, or `None` if the new one would be outside of its heap.
+    pub fn sbrk(&mut self, increment: isize) -> Option<usize> {
+        let bottom = heap_bottom(self.current_task);
+        let tcb = &mut self.tcbs[self.current_task];
+        let old_brk = tcb.brk;
+        let new_brk = if increment >= 0 {
+            old_brk.checked_add(increment as usize)?
+        } else {
+            old_brk.checked_sub(increment.unsigned_abs())?
+        };
+        if new_brk < bottom || new_brk > bottom + USER_HEAP_SIZE {
+            return None;
+        }
+        tcb.brk = new_brk;
+        Some(old_brk)
+    }
+
+    /// Overwrite the canary of the current task's context, for
+    /// `move_to_next_task` to find at its next switch.
+    #[cfg(all(feature = "context-canary", feature = "fault-injection"))]
+    pub fn clobber_context_canary(&mut self) {
+        self.tcbs[self.current_task].cx.canary = !CONTEXT_CANARY;
     }
 
     /// Return current task cx and next task cx
     pub unsafe fn move_to_next_task(&mut self, next_task: usize) -> (*mut TaskContext, *mut TaskContext) {
         let current_task = self.current_task;
+        if CHECK_CONTEXT_CANARY {
+            for task_id in [current_task, next_task] {
+                let canary = self.tcbs[task_id].cx.canary;
+                if canary != CONTEXT_CANARY {
+                    panic!(
+                        "context of task `{}` is corrupted, canary {:#x} instead of {:#x}",
+                        task_id, canary, CONTEXT_CANARY
+                    );
+                }
+            }
+        }
 
         let current_tcb = &mut self.tcbs[current_task];
         let current_task_cx = &mut current_tcb.cx as *mut TaskContext;
-        if current_tcb.status == TaskStatus::Running {
-            current_tcb.status = TaskStatus::Ready;
-        }
+        // `find_next_task` has queued it again if it was still running.
+        kassert!(current_tcb.status != TaskStatus::Running);
         self.stats[current_task].record_schedule_end();
 
+        if next_task != current_task {
+            self.switch_fp(current_task, next_task);
+        }
+
         let next_tcb = &mut self.tcbs[next_task];
         let next_task_cx = &mut next_tcb.cx as *mut TaskContext;
-        assert!(next_tcb.status == TaskStatus::Ready);
+        // Even when the current task continues, `find_next_task` has made it
+        // Ready, so anything else (such as an exited task) is a bug.
+        kassert!(
+            next_tcb.status == TaskStatus::Ready,
+            "scheduling task `{}` which is {:?}", next_task, next_tcb.status
+        );
         next_tcb.status = TaskStatus::Running;
-        self.stats[next_task].record_schedule_begin();
+        self.scheduler.on_schedule(next_tcb);
+        // Its interval begins in `begin_cpu_interval`, right before the switch.
 
         self.current_task = next_task;
+        CURRENT_TASK_ID.store(next_task, atomic::Ordering::Relaxed);
 
         (current_task_cx, next_task_cx)
     }
 
-    pub fn find_next_task(&self) -> Option<usize> {
-        let mut idx = (self.current_task + 1) % self.num_app;
-        for _ in 0..self.num_app {
-            if self.tcbs[idx].status == TaskStatus::Ready {
-                return Some(idx);
-            }
-            idx = (idx + 1) % self.num_app;
+    /// Hand the floating-point registers over from `current_task` to
+    /// `next_task`.
+    ///
+    /// They are only saved if `sstatus.FS` says they were written since they
+    /// were last restored, which is never for tasks that don't use them.
+    /// The flag is conservative: returning to user mode brings back the
+    /// `FS` saved on trap entry.
+    unsafe fn switch_fp(&mut self, current_task: usize, next_task: usize) {
+        let current_tcb = &mut self.tcbs[current_task];
+        if sstatus::read().fs() == FS::Dirty && current_tcb.status != TaskStatus::Zombie {
+            __fp_save(&mut current_tcb.fp);
         }
+        __fp_restore(&self.tcbs[next_task].fp);
+        sstatus::set_fs(FS::Clean);
+    }
+
+    /// Take the runnable task `ActiveScheduler` prefers off the ready queue.
+    ///
+    /// A still running current task is queued first. Ties go to the task
+    /// that has been Ready the longest, so it only keeps the CPU when nobody
+    /// else is at least as preferable, and sleepers woken late don't lose
+    /// their turn to tasks that became ready after they were due.
+    pub fn find_next_task(&mut self) -> Option<usize> {
+        self.check_deadlines();
         if self.tcbs[self.current_task].status == TaskStatus::Running {
-            return Some(self.current_task);
+            self.make_ready(self.current_task, time::get_time());
         }
-        None
+        self.scheduler.pick(&mut self.ready_queue, &self.tcbs)
     }
 
-    pub fn find_next_task_or_exit(&self) -> usize {
-        self.find_next_task().unwrap_or_else(|| finish())
+    /// Like `find_next_task`, but take `task_id` off the ready queue
+    /// whatever the policy prefers, provided it is Ready.
+    pub fn take_ready(&mut self, task_id: usize) -> Option<usize> {
+        if self.tcb(task_id)?.status != TaskStatus::Ready {
+            return None;
+        }
+        let queued = self.ready_queue.remove(task_id);
+        debug_assert!(queued, "ready task `{}` isn't queued", task_id);
+        if self.tcbs[self.current_task].status == TaskStatus::Running {
+            self.make_ready(self.current_task, time::get_time());
+        }
+        Some(task_id)
+    }
+
+    /// Report the tasks that are still runnable after their deadline, once
+    /// per deadline.
+    fn check_deadlines(&mut self) {
+        let now = time::get_time();
+        for task_id in 0..self.num_app {
+            let tcb = &mut self.tcbs[task_id];
+            if !matches!(tcb.status, TaskStatus::Ready | TaskStatus::Running) || tcb.deadline_missed {
+                continue;
+            }
+            if let Some(deadline) = tcb.deadline.filter(|&deadline| deadline < now) {
+                tcb.deadline_missed = true;
+                log_warn!(
+                    "task `{}` missed its deadline by {} clocks",
+                    self.app_names[task_id], now - deadline
+                );
+            }
+        }
+    }
+
+    /// Mark `task_id` Ready since time `since` and queue it.
+    fn make_ready(&mut self, task_id: usize, since: usize) {
+        self.tcbs[task_id].status = TaskStatus::Ready;
+        self.tcbs[task_id].ready_since = since;
+        let (tcbs, scheduler) = (&self.tcbs, &self.scheduler);
+        self.ready_queue.insert_by(task_id, |a, b| {
+            scheduler.order(&tcbs[a], &tcbs[b])
+                .then(tcbs[a].ready_since.cmp(&tcbs[b].ready_since))
+                .is_lt()
+        });
+    }
+
+    /// Set the scheduling parameters of `task_id`, keeping the ready queue
+    /// in order. Returns `false` if there is no such task or it has exited.
+    pub fn set_sched_param(&mut self, task_id: usize, priority: usize, tickets: usize, deadline: Option<usize>) -> bool {
+        let tcb = match self.tcbs[..self.num_app].get_mut(task_id) {
+            Some(tcb) => tcb,
+            None => return false,
+        };
+        if !matches!(tcb.status, TaskStatus::Ready | TaskStatus::Running | TaskStatus::Blocked) {
+            return false;
+        }
+        tcb.set_priority(priority);
+        tcb.tickets = tickets;
+        if tcb.deadline != deadline {
+            tcb.deadline = deadline;
+            tcb.deadline_missed = false;
+        }
+        if tcb.status == TaskStatus::Ready {
+            let since = tcb.ready_since;
+            self.ready_queue.remove(task_id);
+            self.make_ready(task_id, since);
+        }
+        true
+    }
+
+    /// Account a timer tick to the current task. Returns whether it should be
+    /// preempted.
+    pub fn tick(&mut self) -> bool {
+        self.scheduler.on_tick(self.current_task, &mut self.tcbs[..self.num_app], &mut self.ready_queue)
+    }
+
+    /// Make the sleeping tasks whose wake-up time has passed ready again.
+    pub fn wake_sleepers(&mut self) {
+        let now = time::get_time();
+        for task_id in 0..self.num_app {
+            let tcb = &mut self.tcbs[task_id];
+            match tcb.wake_at {
+                // It has been ready since it was due, not since it's noticed.
+                Some(wake_at) if tcb.status == TaskStatus::Blocked && wake_at <= now => {
+                    tcb.wake_at = None;
+                    self.make_ready(task_id, wake_at);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The earliest time a sleeping task is due or an alarm of a live task
+    /// fires, if any.
+    fn next_timed_event(&self) -> Option<usize> {
+        self.tcbs[..self.num_app]
+            .iter()
+            .filter(|tcb| !matches!(tcb.status, TaskStatus::Zombie | TaskStatus::Exited))
+            .flat_map(|tcb| {
+                let wake_at = tcb.wake_at.filter(|_| tcb.status == TaskStatus::Blocked);
+                let alarm_at = tcb.alarm_interval.map(|_| tcb.alarm_at);
+                wake_at.into_iter().chain(alarm_at)
+            })
+            .min()
+    }
+
+    /// Count the alarms of live tasks that are due.
+    pub fn check_alarms(&mut self) {
+        let now = time::get_time();
+        for tcb in self.tcbs[..self.num_app].iter_mut() {
+            if !matches!(tcb.status, TaskStatus::Zombie | TaskStatus::Exited) {
+                tcb.update_alarm(now);
+            }
+        }
+    }
+
+    /// Whether every task has exited, reaped or not. Blocked tasks may still
+    /// become ready.
+    pub fn should_shutdown(&self) -> bool {
+        self.tcbs[..self.num_app]
+            .iter()
+            .all(|tcb| matches!(tcb.status, TaskStatus::Zombie | TaskStatus::Exited))
+    }
+
+    /// Whether some tasks haven't exited, but none of them can ever run
+    /// again: they are all blocked, and none of them on a timer.
+    pub fn is_deadlocked(&self) -> bool {
+        let mut alive = self.tcbs[..self.num_app]
+            .iter()
+            .filter(|tcb| !matches!(tcb.status, TaskStatus::Zombie | TaskStatus::Exited))
+            .peekable();
+        alive.peek().is_some()
+            && alive.all(|tcb| tcb.status == TaskStatus::Blocked && tcb.wake_at.is_none())
     }
 
     pub fn current_task(&self) -> usize {
         self.current_task
     }
 
+    /// Number of apps linked into the kernel, one task each.
+    pub fn num_app(&self) -> usize {
+        self.num_app
+    }
+
+    /// The name of the app `task_id` runs, or `None` if there is no such task.
+    pub fn task_name(&self, task_id: usize) -> Option<&'static str> {
+        self.app_names[..self.num_app].get(task_id).copied()
+    }
+
+    /// The exit code of `task_id`, or `None` if it isn't a zombie.
+    pub fn exit_code(&self, task_id: usize) -> Option<i32> {
+        let tcb = self.tcb(task_id)?;
+        (tcb.status == TaskStatus::Zombie).then(|| tcb.exit_code)
+    }
+
+    /// End another task as if it had exited with `exit_code`. Returns false
+    /// if there is no such task, it has already exited, or it is the
+    /// current task, which must exit instead.
+    ///
+    /// The task isn't running, so it is stopped at a switch, holding no
+    /// lock, and its kernel stack can simply be abandoned.
+    pub fn kill(&mut self, task_id: usize, exit_code: i32) -> bool {
+        if task_id == self.current_task {
+            return false;
+        }
+        let tcb = match self.tcbs[..self.num_app].get_mut(task_id) {
+            Some(tcb) => tcb,
+            None => return false,
+        };
+        match tcb.status {
+            TaskStatus::Ready => {
+                let queued = self.ready_queue.remove(task_id);
+                debug_assert!(queued, "ready task `{}` isn't queued", task_id);
+            }
+            TaskStatus::Blocked => {
+                tcb.wake_at = None;
+                self.events.remove_waiter(task_id);
+            }
+            TaskStatus::UnInit | TaskStatus::Running | TaskStatus::Zombie | TaskStatus::Exited => {
+                return false;
+            }
+        }
+        let tcb = &mut self.tcbs[task_id];
+        tcb.status = TaskStatus::Zombie;
+        tcb.exit_code = exit_code;
+        self.wake_parent(task_id);
+        true
+    }
+
+    /// Block the current task until `task_id` exits. Returns `false` if it
+    /// isn't a child of the current task that has yet to exit.
+    pub fn wait_child(&mut self, task_id: usize) -> bool {
+        let current_task = self.current_task;
+        match self.tcb(task_id) {
+            Some(tcb) if tcb.parent == Some(current_task)
+                && matches!(tcb.status, TaskStatus::Ready | TaskStatus::Running | TaskStatus::Blocked) => {}
+            _ => return false,
+        }
+        let tcb = &mut self.tcbs[current_task];
+        tcb.waiting_for = Some(task_id);
+        tcb.status = TaskStatus::Blocked;
+        true
+    }
+
+    /// Make the parent of `task_id`, which just exited, ready if it is
+    /// blocked waiting for it. Returns the parent if so.
+    fn wake_parent(&mut self, task_id: usize) -> Option<usize> {
+        let parent = self.tcbs[task_id].parent?;
+        let parent_tcb = &mut self.tcbs[parent];
+        if parent_tcb.status != TaskStatus::Blocked || parent_tcb.waiting_for != Some(task_id) {
+            return None;
+        }
+        parent_tcb.waiting_for = None;
+        self.make_ready(parent, time::get_time());
+        Some(parent)
+    }
+
+    /// Block the current task until `event` is signaled. Returns `false`
+    /// if there is no such event.
+    pub fn wait_event(&mut self, event: usize) -> bool {
+        if event >= NUM_EVENTS {
+            return false;
+        }
+        self.events.add_waiter(event, self.current_task);
+        self.tcbs[self.current_task].status = TaskStatus::Blocked;
+        true
+    }
+
+    /// Make every task waiting on `event` ready. Returns how many there
+    /// were, or `None` if there is no such event.
+    pub fn signal_event(&mut self, event: usize) -> Option<usize> {
+        if event >= NUM_EVENTS {
+            return None;
+        }
+        let now = time::get_time();
+        let mut woken = 0;
+        for task_id in self.events.take_waiters(event) {
+            self.make_ready(task_id, now);
+            woken += 1;
+        }
+        Some(woken)
+    }
+
+    /// Put `msg` in the mailbox of `task_id`, waking it if it waits for one.
+    /// Returns `false` if the task isn't alive or its mailbox is full.
+    pub fn send(&mut self, task_id: usize, msg: usize) -> bool {
+        let tcb = match self.tcbs[..self.num_app].get_mut(task_id) {
+            Some(tcb) => tcb,
+            None => return false,
+        };
+        if !matches!(tcb.status, TaskStatus::Ready | TaskStatus::Running | TaskStatus::Blocked) {
+            return false;
+        }
+        if !tcb.mailbox.push(msg) {
+            return false;
+        }
+        if tcb.awaiting_mail {
+            tcb.awaiting_mail = false;
+            self.make_ready(task_id, time::get_time());
+        }
+        true
+    }
+
+    /// Take the exit code of zombie `task_id`, after which the task is gone.
+    pub fn reap(&mut self, task_id: usize) -> Option<i32> {
+        let exit_code = self.exit_code(task_id)?;
+        self.tcbs[task_id].status = TaskStatus::Exited;
+        Some(exit_code)
+    }
+
+    pub fn tcb(&self, task_id: usize) -> Option<&TaskControlBlock> {
+        self.tcbs[..self.num_app].get(task_id)
+    }
+
+    pub fn stat(&self, task_id: usize) -> Option<&TaskStat> {
+        self.stats[..self.num_app].get(task_id)
+    }
+
     pub fn current_stat(&self) -> &TaskStat {
         &self.stats[self.current_task]
     }
@@ -223,21 +1008,13 @@ impl TaskManager {
         &self.tcbs[self.current_task]
     }
 
-    // pub fn current_stat(&mut self) -> &mut TaskStat {
-    //     &mut self.stats[self.current_task]
-    // }
-
-    // pub fn current_tcb(&mut self) -> &mut TaskControlBlock {
-    //     &mut self.tcbs[self.current_task]
-    // }
-
-    // pub fn mut_current_stat(&mut self) -> &mut TaskStat {
-    //     &mut self.stats[self.current_task]
-    // }
+    pub fn with_current_stat_mut<R>(&mut self, f: impl FnOnce(&mut TaskStat) -> R) -> R {
+        f(&mut self.stats[self.current_task])
+    }
 
-    // pub fn mut_current_tcb(&mut self) -> &mut TaskControlBlock {
-    //     &mut self.tcbs[self.current_task]
-    // }
+    pub fn with_current_tcb_mut<R>(&mut self, f: impl FnOnce(&mut TaskControlBlock) -> R) -> R {
+        f(&mut self.tcbs[self.current_task])
+    }
 }
 
 pub unsafe extern "C" fn start_task() {
@@ -251,6 +1028,7 @@ pub unsafe extern "C" fn start_task() {
     let mut task_init_trap_cx = TrapContext::app_init_context(
         task_entry as usize, USER_STACK[current_task].get_sp() as usize
     );
+    record_trap_return();
 
     // We are already in our kernel stack. Don't need to push context to kernel stack.
     __restore(
@@ -258,26 +1036,109 @@ pub unsafe extern "C" fn start_task() {
     );
 }
 
-pub fn exit_and_run_next() {
+/// Make the current task a zombie and run another. Its pending console
+/// output and CPU time are settled first, so neither is lost or blamed on
+/// the next task.
+pub fn exit_and_run_next(exit_code: i32) {
+    console::flush();
     let mut task_mgr = TASK_MANAGER.lock();
+    task_mgr.with_current_stat_mut(|stat| stat.record_schedule_end());
+    let current_task = task_mgr.current_task();
+    let exited = task_mgr.with_current_tcb_mut(|tcb| {
+        // A task exits once. Should it somehow get here again, the first
+        // exit code stands.
+        if tcb.status != TaskStatus::Running {
+            log_warn!("task `{}` exiting again while {:?}", current_task, tcb.status);
+            return false;
+        }
+        tcb.status = TaskStatus::Zombie;
+        tcb.exit_code = exit_code;
+        true
+    });
+    let parent = if exited { task_mgr.wake_parent(current_task) } else { None };
+    match parent.and_then(|parent| task_mgr.take_ready(parent)) {
+        // Straight back to the parent, rather than whoever is next in line:
+        // it is waiting for exactly this.
+        Some(parent) => switch_to(task_mgr, parent),
+        None => {
+            drop(task_mgr);
+            run_next_task();
+        }
+    }
+}
 
-    let current_task = task_mgr.current_task;
-    // println!("task `{current_task}` exited");
-    let current_tcb = &mut task_mgr.tcbs[current_task];
-    current_tcb.status = TaskStatus::Exited;
-    drop(task_mgr);
+/// Block the current task for `clocks`, and run another.
+pub fn sleep_and_run_next(clocks: usize) {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| {
+        tcb.wake_at = Some(time::get_time().saturating_add(clocks));
+        tcb.status = TaskStatus::Blocked;
+    });
     run_next_task();
 }
 
+/// Block the current task until `event` is signaled, and run another.
+/// Returns `false` at once if there is no such event.
+pub fn wait_event_and_run_next(event: usize) -> bool {
+    if !TASK_MANAGER.lock().wait_event(event) {
+        return false;
+    }
+    run_next_task();
+    true
+}
+
+/// Reap `task_id`, blocking and running other tasks until it exits first
+/// if it is a child of the current task. Returns its exit code, or `None`
+/// if there is nothing to reap.
+pub fn waittid_or_run_next(task_id: usize) -> Option<i32> {
+    loop {
+        let mut task_mgr = TASK_MANAGER.lock();
+        if let Some(exit_code) = task_mgr.reap(task_id) {
+            return Some(exit_code);
+        }
+        if !task_mgr.wait_child(task_id) {
+            return None;
+        }
+        drop(task_mgr);
+        run_next_task();
+    }
+}
+
+/// Take the current task's oldest message, blocking and running other
+/// tasks until there is one.
+pub fn recv_or_run_next() -> usize {
+    loop {
+        let mut task_mgr = TASK_MANAGER.lock();
+        let msg = task_mgr.with_current_tcb_mut(|tcb| {
+            let msg = tcb.mailbox.pop();
+            if msg.is_none() {
+                tcb.status = TaskStatus::Blocked;
+                tcb.awaiting_mail = true;
+            }
+            msg
+        });
+        if let Some(msg) = msg {
+            return msg;
+        }
+        drop(task_mgr);
+        run_next_task();
+    }
+}
+
 pub fn run_first_task() {
     let mut task_mgr = TASK_MANAGER.lock();
 
-    let first_task = if task_mgr.num_app > 0 { 0 } else { finish() };
+    let first_task = match task_mgr.find_next_task() {
+        Some(first_task) => first_task,
+        None => finish(&task_mgr),
+    };
     let (_, first_task_cx) = unsafe { task_mgr.move_to_next_task(first_task) };
 
     drop(task_mgr);
 
-    set_next_trigger();
+    if PREEMPTIVE {
+        start_time_slice();
+    }
+    begin_cpu_interval();
     let mut unused = TaskContext::default();
     unsafe {
         __switch(&mut unused, first_task_cx);
@@ -286,36 +1147,365 @@ pub fn run_first_task() {
 
 pub fn run_next_task() {
     let mut task_mgr = TASK_MANAGER.lock();
-    let next_task = task_mgr.find_next_task_or_exit();
+    let next_task = loop {
+        task_mgr.wake_sleepers();
+        if let Some(next_task) = task_mgr.find_next_task() {
+            break next_task;
+        }
+        if task_mgr.should_shutdown() {
+            finish(&task_mgr);
+        }
+        if task_mgr.is_deadlocked() {
+            deadlock(&task_mgr);
+        }
+        // Nothing runs while the CPU idles, the current task included.
+        task_mgr.with_current_stat_mut(|stat| stat.record_schedule_end());
+        drop(task_mgr);
+        let idle_since = time::get_time();
+        idle();
+        IDLE_CLOCKS.fetch_add(time::clocks_since(idle_since), atomic::Ordering::Relaxed);
+        task_mgr = TASK_MANAGER.lock();
+    };
+    switch_to(task_mgr, next_task);
+}
+
+/// Switch to `task_id` if it is Ready, or else yield as usual. Returns
+/// whether it was switched to, once the current task runs again.
+pub fn yield_to(task_id: usize) -> bool {
+    let mut task_mgr = TASK_MANAGER.lock();
+    match task_mgr.take_ready(task_id) {
+        Some(next_task) => {
+            switch_to(task_mgr, next_task);
+            true
+        }
+        None => {
+            drop(task_mgr);
+            run_next_task();
+            false
+        }
+    }
+}
+
+fn switch_to(mut task_mgr: InterruptSafeMutexGuard<TaskManager>, next_task: usize) {
     let (current_task_cx, next_task_cx) = unsafe { task_mgr.move_to_next_task(next_task) };
     drop(task_mgr);
 
-    set_next_trigger();
+    if PREEMPTIVE {
+        start_time_slice();
+    }
+    begin_cpu_interval();
     unsafe {
         __switch(current_task_cx, next_task_cx);
     }
 }
 
+/// Start charging the CPU to the task about to be switched to. Done last,
+/// so that neither picking it nor programming the timer is charged to it,
+/// which would overcharge the first task with whatever ran before it.
+fn begin_cpu_interval() {
+    TASK_MANAGER.lock().with_current_stat_mut(TaskStat::record_schedule_begin);
+}
+
+/// Wait for the next timer interrupt when no task is ready, which is the
+/// next time a sleeper is due or an alarm fires. There is always one:
+/// `run_next_task` has ruled out a deadlock.
+///
+/// Interrupts are disabled in the kernel, so the trap isn't taken. `wfi`
+/// just returns once the timer is pending, and reprogramming the timer
+/// clears it again.
+///
+/// Without preemption the timer interrupt is off and wouldn't end `wfi`, so
+/// this returns right away and the caller polls instead.
+fn idle() {
+    if !PREEMPTIVE {
+        return;
+    }
+    let next_event = TASK_MANAGER.lock().next_timed_event();
+    set_timer(next_event.unwrap_or_else(|| time::get_time() + time::time_slice_clocks()));
+    #[cfg(not(test))]
+    unsafe {
+        asm!("wfi");
+    }
+}
+
+/// Most bytes of its user stack the current task has used.
+pub fn current_user_stack_usage() -> usize {
+    USER_STACK[current_task()].usage()
+}
+
+/// Total clocks the CPU has sat idle since boot.
+pub fn idle_clocks() -> usize {
+    IDLE_CLOCKS.load(atomic::Ordering::Relaxed)
+}
+
+/// Panic if the kernel stack of `task_id` has overflowed.
+pub fn check_guard(task_id: usize) {
+    if !KERNEL_STACK[task_id].guard_intact() {
+        panic!("kernel stack of task `{}` overflowed", task_id);
+    }
+}
+
+/// Overwrite the guard of the kernel stack of `task_id`, for `check_guard`
+/// to find at its next trap.
+#[cfg(feature = "fault-injection")]
+pub fn clobber_guard(task_id: usize) {
+    KERNEL_STACK[task_id].clobber_guard();
+}
+
 fn get_task_base(task_id: usize) -> *mut u8 {
     unsafe {
-        APP_BASE_ADDR.add(task_id * MAX_APP_SIZE)
+        (APP_BASE_ADDR as *mut u8).add(task_id * MAX_APP_SIZE)
     }
 }
 
-fn finish() -> ! {
-    println!("[kernel] All apps have completed.");
+fn heap_bottom(task_id: usize) -> usize {
+    get_task_base(task_id) as usize + MAX_IMAGE_SIZE
+}
+
+fn finish(task_mgr: &TaskManager) -> ! {
+    log_info!("All apps have completed.");
+    for task_id in 0..task_mgr.num_app {
+        log_info!(
+            "  `{}` exited with code {}",
+            task_mgr.app_names[task_id], task_mgr.tcbs[task_id].exit_code
+        );
+        log_debug!(
+            "    stack usage: kernel {}/{}, user {}/{} bytes",
+            KERNEL_STACK[task_id].usage(), KERNEL_STACK_SIZE,
+            USER_STACK[task_id].usage(), USER_STACK_SIZE
+        );
+        log_debug!("    syscalls:");
+        syscall::log_histogram(&task_mgr.stats[task_id].syscall_times);
+    }
+    console::flush();
     sbi::shutdown();
 }
 
-pub fn set_next_trigger() {
-    const TICKS_PER_SEC: usize = 100;
-    let current_time = time::get_time();
-    let delta = time::CLOCK_FREQ / TICKS_PER_SEC;
-    sbi::set_timer(current_time + delta);
+/// Shut down rather than idle forever when no task can make progress.
+fn deadlock(task_mgr: &TaskManager) -> ! {
+    log_warn!("deadlock: all tasks blocked");
+    for task_id in 0..task_mgr.num_app {
+        if task_mgr.tcbs[task_id].status == TaskStatus::Blocked {
+            log_warn!("  `{}` is blocked", task_mgr.app_names[task_id]);
+        }
+    }
+    console::flush();
+    sbi::shutdown();
+}
+
+/// Give the task about to run a full time slice from now.
+pub fn start_time_slice() {
+    SLICE_END.store(time::get_time() + time::time_slice_clocks(), atomic::Ordering::Relaxed);
+    program_next_event();
+}
+
+/// Whether the running task's time slice has ended, or is about to: the
+/// timer may go off up to `TIMER_SLACK` early.
+pub fn time_slice_over() -> bool {
+    time::get_time() + TIMER_SLACK >= SLICE_END.load(atomic::Ordering::Relaxed)
+}
+
+/// Program the timer for the earliest of the end of the time slice, a
+/// sleeper being due, and an alarm firing. Without a fixed tick, there
+/// are no interrupts in between.
+pub fn program_next_event() {
+    let slice_end = SLICE_END.load(atomic::Ordering::Relaxed);
+    let next_event = TASK_MANAGER.lock().next_timed_event();
+    set_timer(next_event.map_or(slice_end, |next_event| next_event.min(slice_end)));
+}
+
+/// Program the timer for `deadline`, unless it is set to go off within
+/// `TIMER_SLACK` of it already. Once it has gone off it is always
+/// reprogrammed, which clears the pending interrupt.
+fn set_timer(deadline: usize) {
+    let programmed = TIMER_DEADLINE.load(atomic::Ordering::Relaxed);
+    if programmed > time::get_time() && programmed.abs_diff(deadline) <= TIMER_SLACK {
+        TIMERS_COALESCED.fetch_add(1, atomic::Ordering::Relaxed);
+        return;
+    }
+    TIMER_DEADLINE.store(deadline, atomic::Ordering::Relaxed);
+    TIMERS_SET.fetch_add(1, atomic::Ordering::Relaxed);
+    sbi::set_timer(time::to_hardware(deadline));
+}
+
+/// Deadlines programmed through SBI and coalesced, see `set_timer`.
+pub fn timer_counts() -> (usize, usize) {
+    (TIMERS_SET.load(atomic::Ordering::Relaxed), TIMERS_COALESCED.load(atomic::Ordering::Relaxed))
+}
+
+/// See `TaskManager::wake_sleepers`.
+pub fn wake_sleepers() {
+    TASK_MANAGER.lock().wake_sleepers()
+}
+
+/// See `TaskManager::tick`.
+pub fn tick() -> bool {
+    TASK_MANAGER.lock().tick()
+}
+
+pub fn record_timer_interrupt() {
+    TASK_MANAGER.lock().with_current_stat_mut(|stat| {
+        stat.timer_interrupts = stat.timer_interrupts.wrapping_add(1);
+    });
+}
+
+pub fn record_voluntary_switch() {
+    TASK_MANAGER.lock().with_current_stat_mut(|stat| stat.voluntary_switches += 1);
+}
+
+pub fn record_involuntary_switch() {
+    TASK_MANAGER.lock().with_current_stat_mut(|stat| stat.involuntary_switches += 1);
 }
 
 pub fn record_syscall(syscall: usize) {
-    let mut task_mgr = TASK_MANAGER.lock();
-    let curent_task = task_mgr.current_task;
-    task_mgr.stats[curent_task].record_syscall(syscall);
+    TASK_MANAGER.lock().with_current_stat_mut(|stat| stat.record_syscall(syscall));
+}
+
+/// Limit the current task to `clocks` of CPU time in total, or lift the
+/// limit if 0.
+pub fn set_current_cpu_limit(clocks: usize) {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.cpu_limit = (clocks != 0).then(|| clocks));
+}
+
+/// Whether the current task has used more CPU time than it may.
+pub fn current_over_cpu_limit() -> bool {
+    let task_mgr = TASK_MANAGER.lock();
+    let used = task_mgr.current_stat().cpu_clocks_so_far();
+    task_mgr.current_tcb().cpu_limit.map_or(false, |limit| used > limit as u64)
+}
+
+pub fn set_current_tickets(tickets: usize) {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.tickets = tickets);
+}
+
+/// Fire the current task's alarm every `clocks`, or stop it if 0.
+pub fn set_current_alarm(clocks: usize) {
+    let interval = if clocks == 0 { None } else { Some(clocks) };
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.set_alarm(interval));
+}
+
+pub fn poll_current_alarm() -> usize {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.poll_alarm())
+}
+
+/// Call `handler` when the current task's alarm fires, or stop if 0.
+pub fn set_current_alarm_handler(handler: usize) {
+    let handler = if handler == 0 { None } else { Some(handler) };
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.alarm_handler = handler);
+}
+
+/// On the way back to user mode, enter the current task's alarm handler
+/// if alarms have fired, with their count as its argument. `cx` is saved
+/// for `take_alarm_context`. A handler isn't entered again until it has
+/// returned.
+pub fn deliver_alarm(cx: &mut TrapContext) {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| {
+        let handler = match tcb.alarm_handler {
+            Some(handler) if tcb.alarm_cx.is_none() => handler,
+            _ => return,
+        };
+        let fired = tcb.poll_alarm();
+        if fired == 0 {
+            return;
+        }
+        tcb.alarm_cx = Some(*cx);
+        cx.sepc = handler;
+        cx.x[10] = fired;
+    });
+}
+
+/// Whether the current task is running its alarm handler.
+pub fn in_alarm_handler() -> bool {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.alarm_cx.is_some())
+}
+
+/// The context the current task's alarm handler interrupted, if it is in
+/// one, which it then leaves.
+pub fn take_alarm_context() -> Option<TrapContext> {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.alarm_cx.take())
+}
+
+/// Checked on every timer interrupt. Polling catches up anyway, for when
+/// the timer is off.
+pub fn check_alarms() {
+    TASK_MANAGER.lock().check_alarms();
+}
+
+/// What the current task's `fd` refers to, if it's open.
+pub fn current_fd(fd: usize) -> Option<FdTarget> {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.fds.get(fd))
+}
+
+/// See `FdTable::dup`.
+pub fn dup_current_fd(fd: usize) -> Option<usize> {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.fds.dup(fd))
+}
+
+pub fn current_is_privileged() -> bool {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.privileged)
+}
+
+pub fn set_current_affinity(mask: usize) {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.affinity = mask);
+}
+
+pub fn set_current_priority(priority: usize) {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.set_priority(priority));
+}
+
+pub fn set_current_deadline(clocks: usize) {
+    TASK_MANAGER.lock().with_current_tcb_mut(|tcb| tcb.set_deadline(clocks));
+}
+
+/// See `TaskManager::check_user_ptr`.
+pub fn check_user_ptr(ptr: usize, len: usize) -> bool {
+    TASK_MANAGER.lock().check_user_ptr(ptr, len)
+}
+
+pub fn current_task() -> usize {
+    TASK_MANAGER.lock().current_task()
+}
+
+/// The current task, if any has run yet. Doesn't take the `TASK_MANAGER` lock.
+pub fn current_task_id() -> Option<usize> {
+    match CURRENT_TASK_ID.load(atomic::Ordering::Relaxed) {
+        usize::MAX => None,
+        task_id => Some(task_id),
+    }
+}
+
+/// Account the time since the task was last in the kernel as user time.
+pub fn record_trap_enter() {
+    TASK_MANAGER.lock().with_current_stat_mut(TaskStat::record_trap_enter);
+}
+
+/// Account the time since the task entered the kernel as kernel time.
+pub fn record_trap_return() {
+    TASK_MANAGER.lock().with_current_stat_mut(TaskStat::record_trap_return);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_app_to_run_without_apps() {
+        let mut task_mgr = TaskManager::with_apps(&[0], [""; MAX_TASK_NUM]);
+        assert_eq!(0, task_mgr.num_app);
+        assert_eq!(None, task_mgr.find_next_task());
+        assert_eq!(None, task_mgr.take_ready(0));
+    }
+
+    #[test]
+    fn kill_spares_the_current_task() {
+        let mut task_mgr = TaskManager::with_apps(&[0, 1, 2], [""; MAX_TASK_NUM]);
+        task_mgr.make_ready(0, 0);
+        task_mgr.make_ready(1, 0);
+        assert!(!task_mgr.kill(0, -1));
+        assert_eq!(TaskStatus::Ready, task_mgr.tcbs[0].status);
+        assert!(task_mgr.kill(1, -1));
+        assert_eq!(Some(-1), task_mgr.exit_code(1));
+        assert!(!task_mgr.kill(1, -1));
+        assert!(!task_mgr.kill(2, -1));
+    }
 }
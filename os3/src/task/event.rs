@@ -0,0 +1,39 @@
+use super::MAX_TASK_NUM;
+
+/// Number of events, with ids `0..NUM_EVENTS`.
+pub const NUM_EVENTS: usize = 8;
+
+// A waiter list is a bit per task id.
+const _: () = assert!(MAX_TASK_NUM <= u64::BITS as usize, "MAX_TASK_NUM is at most 64");
+
+/// The tasks waiting on each event.
+///
+/// Events are edge-triggered: a signal wakes the tasks waiting at that
+/// moment and is then forgotten, so one with no waiters does nothing, and
+/// a wait always blocks until the next signal.
+pub struct Events {
+    waiters: [u64; NUM_EVENTS],
+}
+
+impl Events {
+    pub const fn new() -> Self {
+        Self { waiters: [0; NUM_EVENTS] }
+    }
+
+    pub fn add_waiter(&mut self, event: usize, task_id: usize) {
+        self.waiters[event] |= 1 << task_id;
+    }
+
+    /// Stop `task_id` waiting on any event.
+    pub fn remove_waiter(&mut self, task_id: usize) {
+        for waiters in self.waiters.iter_mut() {
+            *waiters &= !(1 << task_id);
+        }
+    }
+
+    /// Take the tasks waiting on `event`, in order of id.
+    pub fn take_waiters(&mut self, event: usize) -> impl Iterator<Item = usize> {
+        let waiters = core::mem::take(&mut self.waiters[event]);
+        (0..MAX_TASK_NUM).filter(move |&task_id| waiters & (1 << task_id) != 0)
+    }
+}
@@ -0,0 +1,45 @@
+/// Fds a task may have open at once.
+pub const FD_TABLE_SIZE: usize = 8;
+
+/// What an fd refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FdTarget {
+    Stdin,
+    Stdout,
+    Stderr,
+    /// The console, each write a frame, see `console::write_framed`.
+    Framed,
+}
+
+/// A task's open fds. 0, 1 and 2 start out as stdin, stdout and stderr, and
+/// 3 as framed console output. `sys_dup` aliases them to more.
+#[derive(Debug, Clone)]
+pub struct FdTable {
+    fds: [Option<FdTarget>; FD_TABLE_SIZE],
+}
+
+impl Default for FdTable {
+    fn default() -> Self {
+        let mut fds = [None; FD_TABLE_SIZE];
+        fds[0] = Some(FdTarget::Stdin);
+        fds[1] = Some(FdTarget::Stdout);
+        fds[2] = Some(FdTarget::Stderr);
+        fds[3] = Some(FdTarget::Framed);
+        Self { fds }
+    }
+}
+
+impl FdTable {
+    pub fn get(&self, fd: usize) -> Option<FdTarget> {
+        self.fds.get(fd).copied().flatten()
+    }
+
+    /// Open the lowest free fd on what `fd` refers to. Returns `None` if
+    /// `fd` isn't open or the table is full.
+    pub fn dup(&mut self, fd: usize) -> Option<usize> {
+        let target = self.get(fd)?;
+        let new_fd = self.fds.iter().position(Option::is_none)?;
+        self.fds[new_fd] = Some(target);
+        Some(new_fd)
+    }
+}
@@ -0,0 +1,73 @@
+use core::mem::size_of;
+
+use super::stack::{KERNEL_STACK_SIZE, USER_STACK_SIZE};
+use super::{TaskManager, MAX_TASK_NUM};
+use crate::time::parse_usize;
+
+/// Where the kernel is loaded. Must match `BASE_ADDRESS` in `linker.ld` and
+/// `KERNEL_ENTRY_PA` in the Makefile.
+pub const KERNEL_BASE_ADDR: usize = 0x80200000;
+/// The end of the RAM QEMU's `virt` machine has by default, 128 MiB.
+pub const MEMORY_END: usize = 0x88000000;
+
+/// Where app 0 is loaded, each next one `MAX_APP_SIZE` higher. Set at build
+/// time with the `APP_BASE_ADDR` and `MAX_APP_SIZE` environment variables,
+/// e.g. `make run MAX_APP_SIZE=0x40000`, which the user build reads as well
+/// to link the apps at the same addresses.
+///
+/// Everything from `KERNEL_BASE_ADDR` up to `APP_BASE_ADDR` is the
+/// kernel's: besides its code, the boot stack, the stacks of every task and
+/// the images of every app linked in. Only the stacks are known before the
+/// kernel is linked; the rest is checked at boot by `check_kernel_end`.
+pub const APP_BASE_ADDR: usize = match option_env!("APP_BASE_ADDR") {
+    Some(addr) => parse_usize(addr),
+    None => 0x80400000,
+};
+/// Bytes each app gets for its image, .bss and heap.
+pub const MAX_APP_SIZE: usize = match option_env!("MAX_APP_SIZE") {
+    Some(size) => parse_usize(size),
+    None => 0x20000,
+};
+
+/// The stack `rust_main` runs on, in `entry.S`. `TASK_MANAGER` is built on
+/// it, and may be copied once or twice on its way into place.
+pub const BOOT_STACK_SIZE: usize = 0x10000 + 3 * size_of::<TaskManager>();
+
+// The stacks are in the kernel's .bss. Leave at least 512 KiB below the apps
+// for everything else.
+const _: () = assert!(
+    BOOT_STACK_SIZE + MAX_TASK_NUM * (KERNEL_STACK_SIZE + USER_STACK_SIZE) + 0x80000
+        <= APP_BASE_ADDR.saturating_sub(KERNEL_BASE_ADDR),
+    "stacks take too much memory, shrink them, lower MAX_TASK_NUM or raise APP_BASE_ADDR"
+);
+
+// A misplaced app slot would be loaded over the kernel, or past the end of
+// memory, without any other sign.
+const _: () = {
+    assert!(APP_BASE_ADDR > KERNEL_BASE_ADDR, "apps overlap the kernel");
+    let apps_end = match MAX_APP_SIZE.checked_mul(MAX_TASK_NUM) {
+        Some(size) => match APP_BASE_ADDR.checked_add(size) {
+            Some(end) => end,
+            None => panic!("apps overflow the address space"),
+        },
+        None => panic!("apps overflow the address space"),
+    };
+    assert!(apps_end <= MEMORY_END, "apps don't fit in memory");
+    assert!(APP_BASE_ADDR % 0x1000 == 0 && MAX_APP_SIZE % 0x1000 == 0, "apps must be page aligned");
+};
+
+extern "C" {
+    fn ekernel();
+}
+
+/// Panic if the kernel as linked runs into the apps, before any is loaded
+/// over it.
+pub fn check_kernel_end() {
+    let kernel_end = ekernel as usize;
+    assert!(
+        kernel_end <= APP_BASE_ADDR,
+        "the kernel ends at {:#x}, past APP_BASE_ADDR {:#x}: build with a higher APP_BASE_ADDR, or fewer apps",
+        kernel_end,
+        APP_BASE_ADDR
+    );
+}
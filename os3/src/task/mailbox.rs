@@ -0,0 +1,32 @@
+/// Messages a mailbox holds before `sys_send` to it fails.
+pub const MAILBOX_CAPACITY: usize = 16;
+
+/// A task's incoming messages, oldest first, in a fixed-capacity ring.
+#[derive(Debug, Clone, Default)]
+pub struct Mailbox {
+    msgs: [usize; MAILBOX_CAPACITY],
+    head: usize,
+    len: usize,
+}
+
+impl Mailbox {
+    /// Returns `false` if the mailbox is full.
+    pub fn push(&mut self, msg: usize) -> bool {
+        if self.len == MAILBOX_CAPACITY {
+            return false;
+        }
+        self.msgs[(self.head + self.len) % MAILBOX_CAPACITY] = msg;
+        self.len += 1;
+        true
+    }
+
+    pub fn pop(&mut self) -> Option<usize> {
+        if self.len == 0 {
+            return None;
+        }
+        let msg = self.msgs[self.head];
+        self.head = (self.head + 1) % MAILBOX_CAPACITY;
+        self.len -= 1;
+        Some(msg)
+    }
+}
@@ -0,0 +1,148 @@
+use super::MAX_TASK_NUM;
+
+/// The ids of the ready tasks, in the order they are to be run.
+///
+/// A fixed-capacity ring buffer kept sorted on insertion, so that picking
+/// the next task is O(1). The keys tasks are sorted by only change while
+/// they run, never while they are queued.
+pub struct RunQueue {
+    ids: [usize; MAX_TASK_NUM],
+    head: usize,
+    len: usize,
+}
+
+impl RunQueue {
+    pub const fn new() -> Self {
+        Self {
+            ids: [0; MAX_TASK_NUM],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn contains(&self, task_id: usize) -> bool {
+        (0..self.len).any(|i| self.get(i) == task_id)
+    }
+
+    /// The queued ids, front first.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.len).map(|i| self.get(i))
+    }
+
+    pub fn pop_front(&mut self) -> Option<usize> {
+        if self.is_empty() {
+            return None;
+        }
+        let task_id = self.ids[self.head];
+        self.head = (self.head + 1) % MAX_TASK_NUM;
+        self.len -= 1;
+        Some(task_id)
+    }
+
+    /// Take `task_id` out of the queue. Returns whether it was queued.
+    pub fn remove(&mut self, task_id: usize) -> bool {
+        let pos = match (0..self.len).find(|&i| self.get(i) == task_id) {
+            Some(pos) => pos,
+            None => return false,
+        };
+        for i in pos..self.len - 1 {
+            self.set(i, self.get(i + 1));
+        }
+        self.len -= 1;
+        true
+    }
+
+    /// Queue `task_id` behind every task it isn't `preferred_over`, so that
+    /// equally preferable tasks take turns in round-robin order.
+    pub fn insert_by(&mut self, task_id: usize, mut preferred_over: impl FnMut(usize, usize) -> bool) {
+        assert!(self.len < MAX_TASK_NUM, "run queue is full");
+        debug_assert!(!self.contains(task_id), "task `{}` is queued twice", task_id);
+
+        let pos = (0..self.len)
+            .find(|&i| preferred_over(task_id, self.get(i)))
+            .unwrap_or(self.len);
+        for i in (pos..self.len).rev() {
+            self.set(i + 1, self.get(i));
+        }
+        self.set(pos, task_id);
+        self.len += 1;
+    }
+
+    fn get(&self, i: usize) -> usize {
+        self.ids[(self.head + i) % MAX_TASK_NUM]
+    }
+
+    fn set(&mut self, i: usize, task_id: usize) {
+        self.ids[(self.head + i) % MAX_TASK_NUM] = task_id;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equals_take_turns() {
+        let mut queue = RunQueue::new();
+        for task_id in 0..3 {
+            queue.insert_by(task_id, |_, _| false);
+        }
+        assert_eq!(Some(0), queue.pop_front());
+        queue.insert_by(0, |_, _| false);
+        assert_eq!([1, 2, 0], [queue.get(0), queue.get(1), queue.get(2)]);
+    }
+
+    #[test]
+    fn preferred_tasks_go_first() {
+        let mut queue = RunQueue::new();
+        for task_id in [3, 1, 2] {
+            queue.insert_by(task_id, |a, b| a < b);
+        }
+        assert!(queue.iter().eq([1, 2, 3]));
+        assert!(queue.contains(2));
+        assert!(!queue.contains(0));
+    }
+
+    #[test]
+    fn remove_keeps_the_order() {
+        let mut queue = RunQueue::new();
+        for task_id in 0..4 {
+            queue.insert_by(task_id, |_, _| false);
+        }
+        assert!(queue.remove(1));
+        assert!(!queue.remove(1));
+        assert!(queue.iter().eq([0, 2, 3]));
+        assert!(queue.remove(3));
+        assert!(queue.iter().eq([0, 2]));
+    }
+
+    #[test]
+    fn wraps_around() {
+        let mut queue = RunQueue::new();
+        for task_id in 0..MAX_TASK_NUM {
+            queue.insert_by(task_id, |_, _| false);
+        }
+        // Each task goes round once, so the head passes the end of `ids`.
+        for _ in 0..MAX_TASK_NUM {
+            let task_id = queue.pop_front().unwrap();
+            queue.insert_by(task_id, |a, b| a < b);
+        }
+        assert!(queue.iter().eq(0..MAX_TASK_NUM));
+        assert!(!queue.is_empty());
+        while queue.pop_front().is_some() {}
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "run queue is full")]
+    fn refuses_more_than_max_task_num() {
+        let mut queue = RunQueue::new();
+        for task_id in 0..=MAX_TASK_NUM {
+            queue.insert_by(task_id, |_, _| false);
+        }
+    }
+}
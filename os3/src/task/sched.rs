@@ -0,0 +1,421 @@
+use core::cmp::Ordering;
+
+use super::run_queue::RunQueue;
+use super::{SchedPolicy, TaskControlBlock, MAX_TASK_NUM};
+use crate::rand::XorShift64;
+use crate::time;
+
+/// Number of MLFQ levels, level 0 being the highest.
+pub const MLFQ_LEVELS: usize = 3;
+/// Timer ticks a task may run at level 0 before being demoted. The quantum
+/// doubles with each level below.
+const MLFQ_BASE_QUANTUM: usize = 2;
+/// Timer ticks between moving every task back to level 0, so that demoted
+/// tasks don't starve.
+const MLFQ_BOOST_INTERVAL: usize = 100;
+
+/// A scheduling policy.
+///
+/// The one in use is picked at compile time as `ActiveScheduler`, so calls
+/// are static. The ready queue is kept sorted by `order`, ties going to the
+/// task that has been Ready the longest.
+pub trait Scheduler {
+    const POLICY: SchedPolicy;
+
+    fn new() -> Self;
+
+    /// `Less` if `a` should run before `b`.
+    fn order(&self, a: &TaskControlBlock, b: &TaskControlBlock) -> Ordering;
+
+    /// Take the next task to run off `queue`.
+    fn pick(&mut self, queue: &mut RunQueue, _tcbs: &[TaskControlBlock]) -> Option<usize> {
+        queue.pop_front()
+    }
+
+    /// Called when `tcb` is given the CPU.
+    fn on_schedule(&mut self, _tcb: &mut TaskControlBlock) {}
+
+    /// Account a timer tick to `tcbs[current]`. Returns whether it should be
+    /// preempted. The keys of queued tasks may only change if they are taken
+    /// off `queue` meanwhile.
+    fn on_tick(&mut self, _current: usize, _tcbs: &mut [TaskControlBlock], _queue: &mut RunQueue) -> bool {
+        true
+    }
+}
+
+/// The highest priority first, round-robin among equals.
+pub struct RoundRobin;
+
+impl Scheduler for RoundRobin {
+    const POLICY: SchedPolicy = SchedPolicy::RoundRobin;
+
+    fn new() -> Self {
+        Self
+    }
+
+    fn order(&self, a: &TaskControlBlock, b: &TaskControlBlock) -> Ordering {
+        b.priority.cmp(&a.priority)
+    }
+}
+
+/// The smallest stride first. Each run adds the task's pass, which is
+/// inversely proportional to its priority.
+pub struct Stride;
+
+impl Scheduler for Stride {
+    const POLICY: SchedPolicy = SchedPolicy::Stride;
+
+    fn new() -> Self {
+        Self
+    }
+
+    fn order(&self, a: &TaskControlBlock, b: &TaskControlBlock) -> Ordering {
+        (a.stride.wrapping_sub(b.stride) as isize).cmp(&0)
+    }
+
+    fn on_schedule(&mut self, tcb: &mut TaskControlBlock) {
+        tcb.stride = tcb.stride.wrapping_add(tcb.pass);
+    }
+}
+
+/// The highest level first. A task keeps running until it has used up the
+/// quantum of its level, and is then demoted. Every `MLFQ_BOOST_INTERVAL`
+/// ticks all tasks go back to level 0.
+pub struct Mlfq {
+    ticks_since_boost: usize,
+}
+
+impl Mlfq {
+    fn quantum(tcb: &TaskControlBlock) -> usize {
+        MLFQ_BASE_QUANTUM << tcb.level
+    }
+
+    /// Move every task back to level 0. The queued ones are taken off the
+    /// queue meanwhile, and put back in the order they became ready, which
+    /// is all that tells them apart once they are at the same level.
+    fn boost(tcbs: &mut [TaskControlBlock], queue: &mut RunQueue) {
+        let mut queued = [0; MAX_TASK_NUM];
+        let mut len = 0;
+        while let Some(task_id) = queue.pop_front() {
+            queued[len] = task_id;
+            len += 1;
+        }
+        for tcb in tcbs.iter_mut() {
+            tcb.level = 0;
+            tcb.ticks_used = 0;
+        }
+        for &task_id in &queued[..len] {
+            queue.insert_by(task_id, |a, b| tcbs[a].ready_since < tcbs[b].ready_since);
+        }
+    }
+}
+
+impl Scheduler for Mlfq {
+    const POLICY: SchedPolicy = SchedPolicy::Mlfq;
+
+    fn new() -> Self {
+        Self { ticks_since_boost: 0 }
+    }
+
+    fn order(&self, a: &TaskControlBlock, b: &TaskControlBlock) -> Ordering {
+        a.level.cmp(&b.level)
+    }
+
+    /// A task that gave up the CPU before its quantum ran out keeps its
+    /// level, and starts a fresh quantum.
+    fn on_schedule(&mut self, tcb: &mut TaskControlBlock) {
+        tcb.ticks_used = 0;
+    }
+
+    fn on_tick(&mut self, current: usize, tcbs: &mut [TaskControlBlock], queue: &mut RunQueue) -> bool {
+        self.ticks_since_boost += 1;
+        if self.ticks_since_boost >= MLFQ_BOOST_INTERVAL {
+            self.ticks_since_boost = 0;
+            Self::boost(tcbs, queue);
+            return true;
+        }
+
+        let tcb = &mut tcbs[current];
+        tcb.ticks_used += 1;
+        if tcb.ticks_used < Self::quantum(tcb) {
+            return false;
+        }
+        tcb.level = (tcb.level + 1).min(MLFQ_LEVELS - 1);
+        tcb.ticks_used = 0;
+        true
+    }
+}
+
+/// The nearest deadline first. Tasks without a deadline go last.
+pub struct Edf;
+
+impl Scheduler for Edf {
+    const POLICY: SchedPolicy = SchedPolicy::Edf;
+
+    fn new() -> Self {
+        Self
+    }
+
+    fn order(&self, a: &TaskControlBlock, b: &TaskControlBlock) -> Ordering {
+        match (a.deadline, b.deadline) {
+            (Some(a_deadline), Some(b_deadline)) => a_deadline.cmp(&b_deadline),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        }
+    }
+}
+
+/// A random ready task, with a chance proportional to its tickets.
+pub struct Lottery {
+    rng: XorShift64,
+}
+
+impl Scheduler for Lottery {
+    const POLICY: SchedPolicy = SchedPolicy::Lottery;
+
+    fn new() -> Self {
+        Self { rng: XorShift64::new(time::get_time64()) }
+    }
+
+    /// Winners are drawn by `pick`, the queue order doesn't matter.
+    fn order(&self, _a: &TaskControlBlock, _b: &TaskControlBlock) -> Ordering {
+        Ordering::Equal
+    }
+
+    /// Tickets are capped at `MAX_TICKETS`, so the total fits in a u64. If
+    /// there is no winner all the same, the head of the queue runs, rather
+    /// than none.
+    fn pick(&mut self, queue: &mut RunQueue, tcbs: &[TaskControlBlock]) -> Option<usize> {
+        let total: u64 = queue.iter().map(|task_id| tcbs[task_id].tickets as u64).sum();
+        if total == 0 {
+            return queue.pop_front();
+        }
+        let mut winning = self.rng.next_u64() % total;
+        let winner = queue.iter().find(|&task_id| {
+            let tickets = tcbs[task_id].tickets as u64;
+            if winning < tickets {
+                return true;
+            }
+            winning -= tickets;
+            false
+        });
+        match winner {
+            Some(winner) => {
+                queue.remove(winner);
+                Some(winner)
+            }
+            None => queue.pop_front(),
+        }
+    }
+}
+
+#[cfg(any(
+    all(feature = "stride", feature = "mlfq"),
+    all(feature = "stride", feature = "edf"),
+    all(feature = "stride", feature = "lottery"),
+    all(feature = "mlfq", feature = "edf"),
+    all(feature = "mlfq", feature = "lottery"),
+    all(feature = "edf", feature = "lottery"),
+))]
+compile_error!("features `stride`, `mlfq`, `edf` and `lottery` are mutually exclusive");
+
+#[cfg(not(any(feature = "stride", feature = "mlfq", feature = "edf", feature = "lottery")))]
+pub type ActiveScheduler = RoundRobin;
+#[cfg(feature = "stride")]
+pub type ActiveScheduler = Stride;
+#[cfg(feature = "mlfq")]
+pub type ActiveScheduler = Mlfq;
+#[cfg(feature = "edf")]
+pub type ActiveScheduler = Edf;
+#[cfg(feature = "lottery")]
+pub type ActiveScheduler = Lottery;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::task::MAX_TICKETS;
+
+    /// Queue `task_id` as Ready since `since`, the way `make_ready` does.
+    fn make_ready(
+        scheduler: &impl Scheduler,
+        queue: &mut RunQueue,
+        tcbs: &mut [TaskControlBlock],
+        task_id: usize,
+        since: usize,
+    ) {
+        tcbs[task_id].ready_since = since;
+        let tcbs = &*tcbs;
+        queue.insert_by(task_id, |a, b| {
+            scheduler.order(&tcbs[a], &tcbs[b])
+                .then(tcbs[a].ready_since.cmp(&tcbs[b].ready_since))
+                .is_lt()
+        });
+    }
+
+    fn drain(queue: &mut RunQueue) -> [Option<usize>; 4] {
+        [queue.pop_front(), queue.pop_front(), queue.pop_front(), queue.pop_front()]
+    }
+
+    #[test]
+    fn round_robin_runs_the_highest_priority_first() {
+        let mut scheduler = RoundRobin::new();
+        let mut tcbs: [TaskControlBlock; 3] = Default::default();
+        let mut queue = RunQueue::new();
+        tcbs[0].set_priority(16);
+        tcbs[1].set_priority(32);
+        tcbs[2].set_priority(32);
+        for task_id in 0..3 {
+            make_ready(&scheduler, &mut queue, &mut tcbs, task_id, task_id);
+        }
+        assert_eq!(Some(1), scheduler.pick(&mut queue, &tcbs));
+        assert_eq!(Some(2), scheduler.pick(&mut queue, &tcbs));
+        assert_eq!(Some(0), scheduler.pick(&mut queue, &tcbs));
+        assert_eq!(None, scheduler.pick(&mut queue, &tcbs));
+        // Every tick ends the slice.
+        assert!(scheduler.on_tick(0, &mut tcbs, &mut queue));
+    }
+
+    #[test]
+    fn stride_adds_the_pass_on_schedule() {
+        let mut scheduler = Stride::new();
+        let mut tcbs: [TaskControlBlock; 2] = Default::default();
+        tcbs[0].set_priority(2);
+        tcbs[1].set_priority(16);
+        scheduler.on_schedule(&mut tcbs[0]);
+        scheduler.on_schedule(&mut tcbs[1]);
+        assert_eq!(tcbs[0].pass, tcbs[0].stride);
+        assert_eq!(tcbs[1].pass, tcbs[1].stride);
+        assert_eq!(Ordering::Greater, scheduler.order(&tcbs[0], &tcbs[1]));
+    }
+
+    #[test]
+    fn stride_orders_across_overflow() {
+        let scheduler = Stride::new();
+        let mut tcbs: [TaskControlBlock; 2] = Default::default();
+        let mut queue = RunQueue::new();
+        // Task 1 has just wrapped around, so it is ahead of task 0.
+        tcbs[0].stride = usize::MAX - 10;
+        tcbs[1].stride = 5;
+        make_ready(&scheduler, &mut queue, &mut tcbs, 1, 0);
+        make_ready(&scheduler, &mut queue, &mut tcbs, 0, 1);
+        assert_eq!([Some(0), Some(1), None, None], drain(&mut queue));
+    }
+
+    #[test]
+    fn mlfq_demotes_after_the_quantum() {
+        let mut scheduler = Mlfq::new();
+        let mut tcbs: [TaskControlBlock; 1] = Default::default();
+        let mut queue = RunQueue::new();
+        scheduler.on_schedule(&mut tcbs[0]);
+        assert!(!scheduler.on_tick(0, &mut tcbs, &mut queue));
+        assert!(scheduler.on_tick(0, &mut tcbs, &mut queue));
+        assert_eq!(1, tcbs[0].level);
+        // Twice as long at level 1.
+        for _ in 1..MLFQ_BASE_QUANTUM * 2 {
+            assert!(!scheduler.on_tick(0, &mut tcbs, &mut queue));
+        }
+        assert!(scheduler.on_tick(0, &mut tcbs, &mut queue));
+        assert_eq!(2, tcbs[0].level);
+        // And no further than the lowest level.
+        for _ in 0..MLFQ_BASE_QUANTUM * 4 {
+            scheduler.on_tick(0, &mut tcbs, &mut queue);
+        }
+        assert_eq!(MLFQ_LEVELS - 1, tcbs[0].level);
+    }
+
+    #[test]
+    fn mlfq_starts_a_fresh_quantum_on_schedule() {
+        let mut scheduler = Mlfq::new();
+        let mut tcbs: [TaskControlBlock; 1] = Default::default();
+        let mut queue = RunQueue::new();
+        assert!(!scheduler.on_tick(0, &mut tcbs, &mut queue));
+        scheduler.on_schedule(&mut tcbs[0]);
+        assert!(!scheduler.on_tick(0, &mut tcbs, &mut queue));
+        assert_eq!(0, tcbs[0].level);
+    }
+
+    #[test]
+    fn mlfq_boost_requeues_in_ready_order() {
+        let mut scheduler = Mlfq::new();
+        let mut tcbs: [TaskControlBlock; 4] = Default::default();
+        let mut queue = RunQueue::new();
+        tcbs[0].level = 2;
+        tcbs[1].level = 2;
+        tcbs[2].level = 1;
+        tcbs[3].level = 0;
+        make_ready(&scheduler, &mut queue, &mut tcbs, 1, 5);
+        make_ready(&scheduler, &mut queue, &mut tcbs, 2, 9);
+        make_ready(&scheduler, &mut queue, &mut tcbs, 3, 7);
+        scheduler.ticks_since_boost = MLFQ_BOOST_INTERVAL - 1;
+        assert!(scheduler.on_tick(0, &mut tcbs, &mut queue));
+        assert!(tcbs.iter().all(|tcb| tcb.level == 0 && tcb.ticks_used == 0));
+        // All at level 0, the one ready the longest goes first.
+        assert_eq!([Some(1), Some(3), Some(2), None], drain(&mut queue));
+        assert_eq!(0, scheduler.ticks_since_boost);
+    }
+
+    #[test]
+    fn edf_runs_the_nearest_deadline_first() {
+        let mut scheduler = Edf::new();
+        let mut tcbs: [TaskControlBlock; 3] = Default::default();
+        let mut queue = RunQueue::new();
+        tcbs[1].deadline = Some(200);
+        tcbs[2].deadline = Some(100);
+        for task_id in 0..3 {
+            make_ready(&scheduler, &mut queue, &mut tcbs, task_id, task_id);
+        }
+        assert_eq!(Some(2), scheduler.pick(&mut queue, &tcbs));
+        assert_eq!(Some(1), scheduler.pick(&mut queue, &tcbs));
+        assert_eq!(Some(0), scheduler.pick(&mut queue, &tcbs));
+    }
+
+    #[test]
+    fn lottery_only_picks_tasks_with_tickets() {
+        let mut scheduler = Lottery { rng: XorShift64::new(1) };
+        let mut tcbs: [TaskControlBlock; 3] = Default::default();
+        let mut queue = RunQueue::new();
+        tcbs[0].tickets = 0;
+        tcbs[2].tickets = 0;
+        for task_id in 0..3 {
+            make_ready(&scheduler, &mut queue, &mut tcbs, task_id, task_id);
+        }
+        for _ in 0..100 {
+            assert_eq!(Some(1), scheduler.pick(&mut queue, &tcbs));
+            make_ready(&scheduler, &mut queue, &mut tcbs, 1, 0);
+        }
+    }
+
+    #[test]
+    fn lottery_without_tickets_runs_the_head() {
+        let mut scheduler = Lottery { rng: XorShift64::new(1) };
+        let mut tcbs: [TaskControlBlock; 2] = Default::default();
+        let mut queue = RunQueue::new();
+        tcbs[0].tickets = 0;
+        tcbs[1].tickets = 0;
+        make_ready(&scheduler, &mut queue, &mut tcbs, 1, 0);
+        make_ready(&scheduler, &mut queue, &mut tcbs, 0, 1);
+        assert_eq!(Some(1), scheduler.pick(&mut queue, &tcbs));
+        assert_eq!(Some(0), scheduler.pick(&mut queue, &tcbs));
+        assert_eq!(None, scheduler.pick(&mut queue, &tcbs));
+    }
+
+    #[test]
+    fn lottery_sums_max_tickets_without_overflow() {
+        let mut scheduler = Lottery { rng: XorShift64::new(1) };
+        let mut tcbs: [TaskControlBlock; 2] = Default::default();
+        let mut queue = RunQueue::new();
+        tcbs[0].tickets = MAX_TICKETS;
+        tcbs[1].tickets = MAX_TICKETS;
+        let mut wins = [0; 2];
+        for _ in 0..100 {
+            make_ready(&scheduler, &mut queue, &mut tcbs, 0, 0);
+            make_ready(&scheduler, &mut queue, &mut tcbs, 1, 0);
+            let winner = scheduler.pick(&mut queue, &tcbs).unwrap();
+            wins[winner] += 1;
+            queue.pop_front().unwrap();
+        }
+        // Even chances, so both win now and then.
+        assert!(wins[0] > 20 && wins[1] > 20, "wins {:?}", wins);
+    }
+}
@@ -1,9 +1,54 @@
 use core::cell::SyncUnsafeCell;
+use crate::time::parse_usize;
 // use crate::trap::TrapContext;
 
-const KERNEL_STACK_SIZE: usize = 4096 * 2;
-const USER_STACK_SIZE: usize = 4096 * 2;
+#[cfg(target_pointer_width = "64")]
+const DEFAULT_STACK_SIZE: usize = 4096 * 2;
+#[cfg(target_pointer_width = "32")]
+const DEFAULT_STACK_SIZE: usize = 4096;
 
+/// Stack sizes in bytes, set at build time with the `KERNEL_STACK_SIZE` and
+/// `USER_STACK_SIZE` environment variables, e.g.
+/// `make run USER_STACK_SIZE=16384`. They default to 8 KiB on RV64 and
+/// 4 KiB on RV32.
+pub const KERNEL_STACK_SIZE: usize = match option_env!("KERNEL_STACK_SIZE") {
+    Some(size) => parse_usize(size),
+    None => DEFAULT_STACK_SIZE,
+};
+pub const USER_STACK_SIZE: usize = match option_env!("USER_STACK_SIZE") {
+    Some(size) => parse_usize(size),
+    None => DEFAULT_STACK_SIZE,
+};
+const _: () = assert!(KERNEL_STACK_SIZE >= 4096 && KERNEL_STACK_SIZE % STACK_ALIGN == 0);
+const _: () = assert!(USER_STACK_SIZE >= 4096 && USER_STACK_SIZE % STACK_ALIGN == 0);
+
+/// The RISC-V ABI requires `sp` to be 16-byte aligned.
+const STACK_ALIGN: usize = 16;
+
+/// Written to the lowest word of each kernel stack, which the stack only
+/// reaches when it is about to overflow.
+#[cfg(target_pointer_width = "64")]
+const STACK_GUARD: usize = 0xdead_beef_dead_beef;
+#[cfg(target_pointer_width = "32")]
+const STACK_GUARD: usize = 0xdead_beef;
+
+/// Fills unused kernel stacks, so that the bytes a task has used show as
+/// no longer matching it, see `high_water`. User stacks are zeroed instead.
+const STACK_FILL: u8 = 0xa5;
+
+/// Bytes from the deepest one that no longer matches `fill` to the top of
+/// `stack`. A task that happened to write `fill` at the very deepest point
+/// is under-counted by those bytes.
+fn high_water(stack: &[u8], fill: u8) -> usize {
+    match stack.iter().position(|&byte| byte != fill) {
+        Some(deepest) => stack.len() - deepest,
+        None => 0,
+    }
+}
+
+// Page alignment, which is also `STACK_ALIGN` alignment, together with the
+// sizes being multiples of it keeps the tops aligned. `get_sp` masks them
+// all the same, so that a change of layout can't misalign them unnoticed.
 #[repr(align(4096))]
 pub struct KernelStack(SyncUnsafeCell<[u8; KERNEL_STACK_SIZE]>);
 
@@ -17,13 +62,35 @@ impl KernelStack {
     }
 
     pub fn get_sp(&self) -> usize {
+        (self.0.get() as usize + KERNEL_STACK_SIZE) & !(STACK_ALIGN - 1)
+    }
+
+    /// Fill the stack with `STACK_FILL` and put the guard at its bottom.
+    /// It must not be in use.
+    pub fn init_guard(&self) {
         unsafe {
-            let stack = self.0.get();
-            let len = (*stack).len() as isize;
-            (stack as *mut u8).offset(len) as usize
+            core::ptr::write_bytes(self.0.get() as *mut u8, STACK_FILL, KERNEL_STACK_SIZE);
+            (self.0.get() as *mut usize).write_volatile(STACK_GUARD);
         }
     }
 
+    /// Most bytes used since `init_guard`, not counting the guard.
+    pub fn usage(&self) -> usize {
+        let stack = unsafe { &*self.0.get() };
+        high_water(&stack[core::mem::size_of::<usize>()..], STACK_FILL)
+    }
+
+    /// Whether the guard written by `init_guard` is still there.
+    pub fn guard_intact(&self) -> bool {
+        unsafe { (self.0.get() as *const usize).read_volatile() == STACK_GUARD }
+    }
+
+    /// Overwrite the guard, as an overflow would.
+    #[cfg(feature = "fault-injection")]
+    pub fn clobber_guard(&self) {
+        unsafe { (self.0.get() as *mut usize).write_volatile(!STACK_GUARD) }
+    }
+
     // pub fn push_context(&self, cx: TrapContext) -> usize {
     //     unsafe {
     //         let sp = (self.get_sp() as *mut u8)
@@ -39,11 +106,24 @@ impl UserStack {
         Self(SyncUnsafeCell::new([0; USER_STACK_SIZE]))
     }
 
+    pub fn get_bottom(&self) -> *mut u8 {
+        self.0.get() as *mut u8
+    }
+
+    /// Zero the whole stack, wiping what the last task to use it left. It
+    /// must not be in use.
+    pub fn clear(&self) {
+        unsafe { core::ptr::write_bytes(self.get_bottom(), 0, USER_STACK_SIZE) };
+    }
+
+    /// Most bytes used since `clear`. Zeros are common on a stack, so this
+    /// may be a few bytes short.
+    pub fn usage(&self) -> usize {
+        high_water(unsafe { &*self.0.get() }, 0)
+    }
+
     pub fn get_sp(&self) -> *mut u8 {
-        unsafe {
-            let stack = self.0.get();
-            let len = (*stack).len() as isize;
-            (stack as *mut u8).offset(len)
-        }
+        let top = self.get_bottom() as usize + USER_STACK_SIZE;
+        (top & !(STACK_ALIGN - 1)) as *mut u8
     }
 }
@@ -1,12 +1,243 @@
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+#[cfg(not(test))]
 use riscv::register::time;
+use crate::log_warn;
+#[cfg(all(target_pointer_width = "32", not(test)))]
+use riscv::register::timeh;
 
 pub const CLOCK_FREQ: usize = 12500000;
 pub const MILLI_PER_SEC: usize = 1000;
+pub const MICRO_PER_SEC: usize = 1000000;
+pub const NANO_PER_SEC: usize = 1000000000;
 
 pub const CLOCKS_PER_SEC: usize = CLOCK_FREQ / 1;
 pub const CLOCKS_PER_MILLI_SEC: usize = CLOCKS_PER_SEC / MILLI_PER_SEC;
 
+/// Time slices per second, i.e. how often the running task may be
+/// preempted. Set at build time with the `TICKS_PER_SEC` environment
+/// variable, e.g. `make run TICKS_PER_SEC=1000`.
+///
+/// A time slice is `CLOCK_FREQ / TICKS_PER_SEC` clocks, so this must not
+/// exceed `CLOCK_FREQ`.
+pub const TICKS_PER_SEC: usize = match option_env!("TICKS_PER_SEC") {
+    Some(ticks) => parse_usize(ticks),
+    None => 100,
+};
+const _: () = assert!(TICKS_PER_SEC > 0 && TICKS_PER_SEC <= CLOCK_FREQ);
 
+/// Added to every clock count read, so that a test run can start as if the
+/// machine had been up long enough to overflow a 32-bit count, e.g.
+/// `make run CLOCK_OFFSET=4294000000`. The hardware is programmed with the
+/// offset taken back off, see `to_hardware`.
+pub const CLOCK_OFFSET: u64 = match option_env!("CLOCK_OFFSET") {
+    Some(offset) => parse_u64(offset),
+    None => 0,
+};
+
+/// Parse a decimal number, or a hexadecimal one starting with `0x`, at
+/// compile time, for settings taken from the build environment.
+pub const fn parse_usize(s: &str) -> usize {
+    let n = parse_u64(s);
+    assert!(n <= usize::MAX as u64, "number too large");
+    n as usize
+}
+
+pub const fn parse_u64(s: &str) -> u64 {
+    let bytes = s.as_bytes();
+    let (radix, mut i) = if bytes.len() > 2 && bytes[0] == b'0' && bytes[1] == b'x' {
+        (16, 2)
+    } else {
+        (10, 0)
+    };
+    assert!(i < bytes.len(), "empty number");
+    let mut n: u64 = 0;
+    while i < bytes.len() {
+        let digit = match bytes[i] {
+            b'0'..=b'9' => bytes[i] - b'0',
+            b'a'..=b'f' if radix == 16 => bytes[i] - b'a' + 10,
+            b'A'..=b'F' if radix == 16 => bytes[i] - b'A' + 10,
+            _ => panic!("not a number"),
+        };
+        n = match n.checked_mul(radix) {
+            Some(n) => match n.checked_add(digit as u64) {
+                Some(n) => n,
+                None => panic!("number too large"),
+            },
+            None => panic!("number too large"),
+        };
+        i += 1;
+    }
+    n
+}
+
+/// Clocks in a time slice. The timer fires at the end of one, or earlier
+/// for a sleeper or an alarm, see `task::program_next_event`.
+pub const fn time_slice_clocks() -> usize {
+    CLOCK_FREQ / TICKS_PER_SEC
+}
+
+
+/// The clock count, truncated to its low 32 bits on RV32.
 pub fn get_time() -> usize {
-    time::read()
+    get_time64() as usize
+}
+
+/// The full clock count, plus `CLOCK_OFFSET`.
+pub fn get_time64() -> u64 {
+    read_time64().wrapping_add(CLOCK_OFFSET)
+}
+
+/// `deadline`, a clock count as returned by `get_time`, as the hardware
+/// counts it.
+pub fn to_hardware(deadline: usize) -> usize {
+    deadline.wrapping_sub(CLOCK_OFFSET as usize)
+}
+
+#[cfg(all(target_pointer_width = "64", not(test)))]
+fn read_time64() -> u64 {
+    time::read() as u64
+}
+
+/// Host unit tests have no `time` CSR, and see a clock that stands still.
+#[cfg(test)]
+fn read_time64() -> u64 {
+    0
+}
+
+/// On RV32 the low and high halves of the count are read from `time` and
+/// `timeh` separately, and the low half may wrap in between: reading
+/// `timeh` = 0, then `time` after it went from 0xffff_ffff to 0, gives
+/// 0x0_0000_0000 instead of 0x1_0000_0000, about 343 s in the past.
+///
+/// Reading `timeh` again after `time` detects that. If it's unchanged, the
+/// low half was read while the high half held that value, so the pair is
+/// consistent. Otherwise the wrap just happened and the next try is far
+/// from the following one.
+#[cfg(all(target_pointer_width = "32", not(test)))]
+fn read_time64() -> u64 {
+    loop {
+        let high = timeh::read();
+        let low = time::read();
+        if timeh::read() == high {
+            return (high as u64) << 32 | low as u64;
+        }
+    }
+}
+
+static WENT_BACKWARD: AtomicBool = AtomicBool::new(false);
+
+/// When `init` completed, see `uptime_ms`.
+static BOOT_TIME: AtomicUsize = AtomicUsize::new(0);
+
+/// Take now as the end of boot.
+pub fn mark_boot() {
+    BOOT_TIME.store(get_time(), Ordering::Relaxed);
+}
+
+/// Milliseconds since `init` completed.
+pub fn uptime_ms() -> usize {
+    clocks_since(BOOT_TIME.load(Ordering::Relaxed)) / CLOCKS_PER_MILLI_SEC
+}
+
+/// Clocks from `earlier` to `later`, both read with `get_time64`.
+///
+/// Some SBI and QEMU setups have been seen to make the clock jump back,
+/// after a reset for instance. Rather than die of it, a backward step is
+/// taken as no time at all, and reported the first time it happens.
+pub fn clocks_between64(earlier: u64, later: u64) -> u64 {
+    if later < earlier && !WENT_BACKWARD.swap(true, Ordering::Relaxed) {
+        log_warn!("time went backward from {:#x} to {:#x}", earlier, later);
+    }
+    later.saturating_sub(earlier)
+}
+
+/// Clocks since `earlier`, see `clocks_between64`.
+pub fn clocks_since64(earlier: u64) -> u64 {
+    clocks_between64(earlier, get_time64())
+}
+
+/// `clocks_between64` for counts read with `get_time`.
+#[cfg(target_pointer_width = "64")]
+pub fn clocks_between(earlier: usize, later: usize) -> usize {
+    clocks_between64(earlier as u64, later as u64) as usize
+}
+
+/// On RV32 the count read with `get_time` wraps every 2^32 clocks, about
+/// 343 s, so the difference is taken modulo that. It is right for any
+/// interval shorter than the wrap, one that crosses it included, but a
+/// backward step can't be told from a wrap, and comes out as a long time.
+#[cfg(target_pointer_width = "32")]
+pub fn clocks_between(earlier: usize, later: usize) -> usize {
+    later.wrapping_sub(earlier)
+}
+
+/// Clocks since `earlier`, see `clocks_between`.
+pub fn clocks_since(earlier: usize) -> usize {
+    clocks_between(earlier, get_time())
+}
+
+// Whole seconds and the remainder are converted separately, so the
+// multiplication can't overflow however long the machine has been up.
+
+pub fn get_time_ms() -> usize {
+    let t = get_time();
+    t / CLOCKS_PER_SEC * MILLI_PER_SEC + t % CLOCKS_PER_SEC * MILLI_PER_SEC / CLOCKS_PER_SEC
+}
+
+pub fn get_time_us() -> usize {
+    let t = get_time();
+    t / CLOCKS_PER_SEC * MICRO_PER_SEC + t % CLOCKS_PER_SEC * MICRO_PER_SEC / CLOCKS_PER_SEC
+}
+
+pub fn get_time_ns() -> u64 {
+    // A u128 product can't overflow for any 64-bit clock count, and the
+    // quotient fits in a u64 for centuries of uptime.
+    (get_time64() as u128 * NANO_PER_SEC as u128 / CLOCK_FREQ as u128) as u64
+}
+
+/// `clocks` in units of `1 / per_sec` seconds, rounded down. The u128
+/// product can't overflow.
+fn clocks_to(clocks: u64, per_sec: usize) -> u64 {
+    (clocks as u128 * per_sec as u128 / CLOCK_FREQ as u128) as u64
+}
+
+pub fn clocks_to_ms(clocks: u64) -> u64 {
+    clocks_to(clocks, MILLI_PER_SEC)
+}
+
+pub fn clocks_to_us(clocks: u64) -> u64 {
+    clocks_to(clocks, MICRO_PER_SEC)
+}
+
+/// Clocks in `sec` seconds and `nsec` nanoseconds, rounded up so that a
+/// wait is never shorter than asked, and saturating at `usize::MAX`.
+pub fn clocks_from_sec_nsec(sec: usize, nsec: usize) -> usize {
+    let ns = sec as u128 * NANO_PER_SEC as u128 + nsec as u128;
+    let clocks = (ns * CLOCK_FREQ as u128 + NANO_PER_SEC as u128 - 1) / NANO_PER_SEC as u128;
+    usize::try_from(clocks).unwrap_or(usize::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forward_deltas() {
+        assert_eq!(0, clocks_between64(5, 5));
+        assert_eq!(10, clocks_between64(5, 15));
+        assert_eq!(u64::MAX, clocks_between64(0, u64::MAX));
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(10, clocks_between(5, 15));
+    }
+
+    /// The only test to step back, as the flag is global.
+    #[test]
+    fn a_backward_step_is_no_time_and_warned_about_once() {
+        assert!(!WENT_BACKWARD.load(Ordering::Relaxed));
+        assert_eq!(0, clocks_between64(100, 50));
+        assert!(WENT_BACKWARD.load(Ordering::Relaxed));
+        // Already warned, and still no time.
+        assert_eq!(0, clocks_between64(u64::MAX, 0));
+        assert!(WENT_BACKWARD.load(Ordering::Relaxed));
+    }
 }
@@ -1,57 +1,290 @@
 mod context;
 
 use crate::task::{
-    run_next_task, exit_and_run_next, set_next_trigger,
+    run_next_task, exit_and_run_next, current_task, start_time_slice,
+    time_slice_over, program_next_event, wake_sleepers,
+    record_trap_enter, record_trap_return, check_guard, tick, PREEMPTIVE,
+    record_timer_interrupt, current_over_cpu_limit, record_involuntary_switch,
+    check_alarms, deliver_alarm, take_alarm_context, timer_counts,
+    current_task_id, MAX_TASK_NUM,
 };
+use crate::console;
 use crate::println;
-use crate::syscall::syscall;
+use crate::sbi::{system_reset, ResetReason, ResetType};
+use crate::syscall::{syscall, SYSCALL_SIGRETURN, SYSCALL_DUMP_REGS};
 pub use context::TrapContext;
+#[cfg(not(test))]
 use core::arch::global_asm;
+use core::sync::atomic::{AtomicUsize, Ordering};
 use riscv::register::{
     scause::{self, Exception, Interrupt, Trap},
+    sstatus::SPP,
     stval, stvec,
 };
 
+/// The bit of `scause` set for interrupts.
+const SCAUSE_INTERRUPT: usize = 1 << (usize::BITS - 1);
+
+/// `scause` exception codes of the alignment faults. The riscv crate has no
+/// variant for a misaligned load, which it reports as `Unknown`, so they are
+/// told apart by code.
+const MISALIGNED_FAULTS: [usize; 3] = [0, 4, 6];
+
+// Counters since boot. `fetch_add` wraps around on overflow.
+static TRAPS: AtomicUsize = AtomicUsize::new(0);
+static TIMER_INTERRUPTS: AtomicUsize = AtomicUsize::new(0);
+static SYSCALLS: AtomicUsize = AtomicUsize::new(0);
+
+/// Where each task's registers were saved on its last trap from user mode,
+/// 0 until it has trapped. Kept out of `TASK_MANAGER` for `kassert!`, which
+/// may fail with it locked.
+static TRAP_CONTEXTS: [AtomicUsize; MAX_TASK_NUM] = {
+    #[allow(clippy::declare_interior_mutable_const)]
+    const TRAP_CONTEXT: AtomicUsize = AtomicUsize::new(0);
+    [TRAP_CONTEXT; MAX_TASK_NUM]
+};
+
+/// Traps taken in the kernel itself: 1 while the first is being reported,
+/// more if reporting it traps again. The kernel never returns from one, so
+/// it never goes back down.
+static TRAP_DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TrapStats {
+    pub traps: usize,
+    pub timer_interrupts: usize,
+    pub syscalls: usize,
+    /// Timer interrupts taken while the current task was running.
+    pub task_timer_interrupts: usize,
+    /// Timer deadlines programmed through SBI, and those skipped for being
+    /// close enough to the one already set.
+    pub set_timer_calls: usize,
+    pub set_timer_skipped: usize,
+}
+
+/// The counters of all tasks, `task_timer_interrupts` is left to the caller.
+pub fn trap_stats() -> TrapStats {
+    let (set_timer_calls, set_timer_skipped) = timer_counts();
+    TrapStats {
+        traps: TRAPS.load(Ordering::Relaxed),
+        timer_interrupts: TIMER_INTERRUPTS.load(Ordering::Relaxed),
+        syscalls: SYSCALLS.load(Ordering::Relaxed),
+        task_timer_interrupts: 0,
+        set_timer_calls,
+        set_timer_skipped,
+    }
+}
+
+#[cfg(not(test))]
 global_asm!(include_str!("trap/trap.S"));
 extern "C" {
-    fn __all_traps();
+    fn __kernel_trap();
     pub fn __restore(cx: usize) -> !;
 }
 
+/// Point `stvec` at the kernel's trap entry. `__restore` switches it to
+/// `__all_traps` on the way to user mode, and `__all_traps` back again.
 pub fn init() {
     unsafe {
-        stvec::write(__all_traps as usize, stvec::TrapMode::Direct);
+        stvec::write(__kernel_trap as usize, stvec::TrapMode::Direct);
     }
 }
 
 #[no_mangle]
 pub extern "C" fn trap_handler(cx: &mut TrapContext) -> &mut TrapContext {
     let scause = scause::read();
+    let cause = describe_cause(scause.bits());
     let stval = stval::read();
+    // `__kernel_trap` should have taken it, but a trap in the kernel is a
+    // bug however it got here.
+    if cx.sstatus.spp() == SPP::Supervisor {
+        kernel_trap_handler(cx);
+    }
+    if let Some(task_id) = current_task_id() {
+        TRAP_CONTEXTS[task_id].store(cx as *const TrapContext as usize, Ordering::Relaxed);
+    }
+    check_guard(current_task());
+    record_trap_enter();
+    TRAPS.fetch_add(1, Ordering::Relaxed);
 
     match scause.cause() {
-        Trap::Interrupt(Interrupt::SupervisorTimer) => {
-            // println!("\nscheduling");
-            set_next_trigger();
-            run_next_task();
+        // Never enabled without preemption.
+        Trap::Interrupt(Interrupt::SupervisorTimer) if PREEMPTIVE => {
+            TIMER_INTERRUPTS.fetch_add(1, Ordering::Relaxed);
+            record_timer_interrupt();
+            check_alarms();
+            // Only checked here, so a task may go over by up to a time slice.
+            if current_over_cpu_limit() {
+                println!(
+                    "[kernel] Application `{}` is over its CPU budget, kernel killed it.",
+                    current_task()
+                );
+                exit_and_run_next(-1);
+            }
+            if time_slice_over() {
+                if tick() {
+                    record_involuntary_switch();
+                    run_next_task();
+                } else {
+                    start_time_slice();
+                }
+            } else {
+                // A sleeper is due or an alarm fired. The sleeper waits for
+                // the slice to end like it did with a fixed tick.
+                wake_sleepers();
+                program_next_event();
+            }
         }
         Trap::Exception(Exception::UserEnvCall) => {
+            // The only place `sepc` is moved past the `ecall`, and before the
+            // syscall runs: syscalls that switch away (yield, sleep, read)
+            // return through this same context, and must resume after it.
             cx.sepc += 4;
+            SYSCALLS.fetch_add(1, Ordering::Relaxed);
             let id = cx.x[17];
             let args = [cx.x[10], cx.x[11], cx.x[12]];
+            // Only the trap handler has the registers. `sepc` is already the
+            // instruction after the `ecall`, where the task resumes.
+            if id == SYSCALL_DUMP_REGS {
+                println!("[kernel] registers of application `{}`:", current_task());
+                cx.dump();
+            }
             cx.x[10] = syscall(id, args) as usize;
+            // Back to where the alarm handler was entered, `a0` included.
+            if id == SYSCALL_SIGRETURN {
+                if let Some(alarm_cx) = take_alarm_context() {
+                    *cx = alarm_cx;
+                }
+            }
+        }
+        Trap::Exception(_) if MISALIGNED_FAULTS.contains(&scause.bits()) => {
+            println!(
+                "[kernel] {} in application `{}` at {:#x}, bad addr = {:#x}, kernel killed it.",
+                cause, current_task(), cx.sepc, stval
+            );
+            cx.dump();
+            exit_and_run_next(-4);
+        }
+        Trap::Exception(
+            Exception::StoreFault
+            | Exception::StorePageFault
+            | Exception::LoadFault
+            | Exception::LoadPageFault,
+        ) => {
+            println!(
+                "[kernel] {} in application `{}`, bad addr = {:#x}, kernel killed it.",
+                cause, current_task(), stval
+            );
+            cx.dump();
+            exit_and_run_next(-2);
         }
-        Trap::Exception(Exception::StoreFault | Exception::StorePageFault) => {
-            println!("[kernel] PageFault in application, kernel killed it.");
-            exit_and_run_next();
+        Trap::Exception(Exception::Breakpoint) => {
+            println!(
+                "[kernel] {} in application `{}` at {:#x}, resuming.",
+                cause, current_task(), cx.sepc
+            );
+            cx.sepc += insn_len(cx.sepc);
         }
         Trap::Exception(Exception::IllegalInstruction) => {
-            println!("[kernel] IllegalInstruction in application, kernel killed it.");
-            exit_and_run_next();
+            println!(
+                "[kernel] {} in application `{}` at {:#x}, kernel killed it.",
+                cause, current_task(), cx.sepc
+            );
+            cx.dump();
+            exit_and_run_next(-3);
         }
-        unknown => {
-            panic!("Unsupported trap {:?}, stval = {:#x}!", unknown, stval);
+        _ => {
+            cx.dump();
+            panic!("Unsupported trap: {} ({:#x}), stval = {:#x}!", cause, scause.bits(), stval);
         }
     }
+    deliver_alarm(cx);
+    record_trap_return();
     cx
 }
+
+/// A copy of the registers the current task last trapped with, if it has.
+/// Doesn't take any lock.
+pub fn current_trap_context() -> Option<TrapContext> {
+    let cx = TRAP_CONTEXTS[current_task_id()?].load(Ordering::Relaxed);
+    // Saved on the task's kernel stack, which stays where it is.
+    (cx != 0).then(|| unsafe { (cx as *const TrapContext).read_volatile() })
+}
+
+/// A trap taken in the kernel itself, from `__kernel_trap` or one that
+/// reached `trap_handler` anyway. Never the application's fault, so the
+/// kernel dumps its registers and panics.
+///
+/// Taken while reporting another such trap, it's a double fault, and the
+/// kernel shuts down right after the dump: the panic handler may well touch
+/// whatever broke the report, and trap again. A trap from user mode being
+/// handled doesn't count, so a fault in a syscall is reported as any
+/// other.
+#[no_mangle]
+pub extern "C" fn kernel_trap_handler(cx: &TrapContext) -> ! {
+    let depth = TRAP_DEPTH.fetch_add(1, Ordering::Relaxed) + 1;
+    if depth > 2 {
+        // Reporting the double fault trapped too, printing may be to blame.
+        system_reset(ResetType::Shutdown, ResetReason::SystemFailure);
+    }
+    let scause = scause::read();
+    if depth == 2 {
+        println!(
+            "[kernel] double fault: {} in kernel at {:#x} while reporting a trap, stval = {:#x}, registers:",
+            describe_cause(scause.bits()), cx.sepc, stval::read()
+        );
+        cx.dump();
+        console::flush();
+        system_reset(ResetType::Shutdown, ResetReason::SystemFailure);
+    }
+    println!("[kernel] trap in kernel, registers:");
+    cx.dump();
+    panic!(
+        "{} in kernel at {:#x}, stval = {:#x}",
+        describe_cause(scause.bits()), cx.sepc, stval::read()
+    );
+}
+
+/// What the trap with `scause` value `scause` is, as the privileged spec
+/// names it.
+pub fn describe_cause(scause: usize) -> &'static str {
+    if scause & SCAUSE_INTERRUPT != 0 {
+        return match scause & !SCAUSE_INTERRUPT {
+            1 => "Supervisor software interrupt",
+            3 => "Machine software interrupt",
+            5 => "Supervisor timer interrupt",
+            7 => "Machine timer interrupt",
+            9 => "Supervisor external interrupt",
+            11 => "Machine external interrupt",
+            _ => "Unknown interrupt",
+        };
+    }
+    match scause {
+        0 => "Instruction address misaligned",
+        1 => "Instruction access fault",
+        2 => "Illegal instruction",
+        3 => "Breakpoint",
+        4 => "Load address misaligned",
+        5 => "Load access fault",
+        6 => "Store/AMO address misaligned",
+        7 => "Store/AMO access fault",
+        8 => "Environment call from U-mode",
+        9 => "Environment call from S-mode",
+        11 => "Environment call from M-mode",
+        12 => "Instruction page fault",
+        13 => "Load page fault",
+        15 => "Store/AMO page fault",
+        _ => "Unknown exception",
+    }
+}
+
+/// Length in bytes of the instruction at `sepc`: 2 for compressed ones,
+/// whose lowest two bits aren't both set, 4 otherwise.
+///
+/// Use it to step over any instruction that trapped. `ecall` has no
+/// compressed form, so the syscall path just adds 4.
+pub fn insn_len(sepc: usize) -> usize {
+    let low = unsafe { (sepc as *const u16).read_volatile() };
+    if low & 0b11 == 0b11 { 4 } else { 2 }
+}
@@ -1,6 +1,18 @@
 use riscv::register::sstatus::{self, Sstatus, SPP};
+use riscv::register::{scause, stval};
+use super::describe_cause;
+use crate::{print, println};
+
+/// ABI names of `x0` to `x31`.
+const REG_NAMES: [&str; 32] = [
+    "zero", "ra", "sp", "gp", "tp", "t0", "t1", "t2",
+    "s0", "s1", "a0", "a1", "a2", "a3", "a4", "a5",
+    "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7",
+    "s8", "s9", "s10", "s11", "t3", "t4", "t5", "t6",
+];
 
 #[repr(C)]
+#[derive(Debug, Clone, Copy)]
 pub struct TrapContext {
     pub x: [usize; 32],
     pub sstatus: Sstatus,
@@ -26,4 +38,24 @@ impl TrapContext {
 
         cx
     }
+
+    /// Print the saved registers, four to a line, then `sepc`, `sstatus`
+    /// and the cause of the trap being handled.
+    pub fn dump(&self) {
+        for (row, regs) in self.x.chunks(4).enumerate() {
+            for (i, reg) in regs.iter().enumerate() {
+                print!("{:>4}: {:#018x}  ", REG_NAMES[row * 4 + i], reg);
+            }
+            println!();
+        }
+        println!(
+            "sepc: {:#018x}  sstatus: SPP={:?} SPIE={} FS={:?}",
+            self.sepc, self.sstatus.spp(), self.sstatus.spie(), self.sstatus.fs()
+        );
+        let scause = scause::read().bits();
+        println!(
+            "scause: {:#018x} ({})  stval: {:#018x}",
+            scause, describe_cause(scause), stval::read()
+        );
+    }
 }
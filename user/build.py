@@ -1,7 +1,11 @@
 import os
 
-base_address = 0x80400000
-step = 0x20000
+# Must agree with the kernel, see `os3/src/task/layout.rs`.
+base_address = int(os.getenv("APP_BASE_ADDR", "0x80400000"), 0)
+step = int(os.getenv("MAX_APP_SIZE", "0x20000"), 0)
+# The top of each slot is the app's heap, `USER_HEAP_SIZE` in
+# `os3/src/task.rs`. The linker script checks that .bss stays below it.
+max_image_size = step - 0x4000
 linker = "src/linker.ld"
 
 app_id = 0
@@ -11,9 +15,12 @@ chapter = os.getenv("CHAPTER")
 
 for app in apps:
     app = app[: app.find(".")]
+    link_args = "-Ttext=%x" % (base_address + step * app_id)
+    if chapter == '3':
+        link_args += " --defsym=MAX_IMAGE_SIZE=%#x" % max_image_size
     os.system(
-        "cargo rustc --bin %s --release -- -Clink-args=-Ttext=%x"
-        % (app, base_address + step * app_id)
+        "cargo rustc --bin %s --release -- '-Clink-args=%s'"
+        % (app, link_args)
     )
     print(
         "[build.py] application %s start with address %s"
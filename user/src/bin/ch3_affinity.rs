@@ -0,0 +1,32 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sys_set_affinity, task_info, TaskInfo};
+
+/// 正确输出：（无报错信息）
+/// Test affinity OK!
+
+fn affinity() -> usize {
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    info.affinity
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Any hart until set.
+    assert_eq!(usize::MAX, affinity());
+    assert_eq!(-1, sys_set_affinity(0));
+    assert_eq!(usize::MAX, affinity());
+
+    assert_eq!(0, sys_set_affinity(0b101));
+    assert_eq!(0b101, affinity());
+    // Harts that don't exist may be named too, and the task still runs.
+    assert_eq!(0, sys_set_affinity(0b100));
+    assert_eq!(0b100, affinity());
+    println!("Test affinity OK!");
+    0
+}
@@ -0,0 +1,36 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, sleep_blocking, sys_poll_alarm, sys_set_alarm};
+
+/// 正确输出：（无报错信息）
+/// alarm fired ... times in ...ms
+/// Test alarm OK!
+
+const ALARM_MS: usize = 100;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let start = get_time();
+    assert_eq!(0, sys_set_alarm(ALARM_MS));
+    sleep_blocking(1000);
+    let fired = sys_poll_alarm() as usize;
+    let elapsed = (get_time() - start) as usize;
+    println!("alarm fired {} times in {}ms", fired, elapsed);
+    // About 10 times a second, give or take the millisecond rounding at
+    // either end.
+    let expected = elapsed / ALARM_MS;
+    assert!(fired + 1 >= expected && fired <= expected + 1);
+    assert!(fired >= 9);
+
+    // Polling takes the count.
+    assert_eq!(0, sys_poll_alarm());
+    assert_eq!(0, sys_set_alarm(0));
+    sleep_blocking(2 * ALARM_MS);
+    assert_eq!(0, sys_poll_alarm());
+    println!("Test alarm OK!");
+    0
+}
@@ -0,0 +1,60 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::sync::atomic::{AtomicUsize, Ordering};
+use user_lib::{get_time, sys_set_alarm, sys_set_alarm_handler, sys_sigreturn};
+
+/// 正确输出：（无报错信息）
+/// Test alarm handler OK!
+
+const ALARM_MS: usize = 20;
+const WANTED: usize = 5;
+const TIMEOUT_MS: isize = 5000;
+
+static FIRED: AtomicUsize = AtomicUsize::new(0);
+static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+extern "C" fn on_alarm(fired: usize) {
+    FIRED.fetch_add(fired, Ordering::Relaxed);
+    CALLS.fetch_add(1, Ordering::Relaxed);
+    sys_sigreturn();
+    unreachable!("sys_sigreturn returned in the handler");
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Not in a handler.
+    assert_eq!(-1, sys_sigreturn());
+    assert_eq!(-1, sys_set_alarm_handler(usize::MAX & !1));
+
+    assert_eq!(0, sys_set_alarm_handler(on_alarm as usize));
+    assert_eq!(0, sys_set_alarm(ALARM_MS));
+    // Work the handler keeps interrupting, which must come out the same
+    // as if it never had.
+    let start = get_time();
+    let (mut a, mut b) = (0u64, 1u64);
+    let mut steps = 0u64;
+    while FIRED.load(Ordering::Relaxed) < WANTED && get_time() - start < TIMEOUT_MS {
+        let next = a.wrapping_add(b);
+        a = b;
+        b = next;
+        steps += 1;
+    }
+    assert_eq!(0, sys_set_alarm(0));
+    assert_eq!(0, sys_set_alarm_handler(0));
+    assert!(FIRED.load(Ordering::Relaxed) >= WANTED, "the alarm handler didn't run");
+    assert!(CALLS.load(Ordering::Relaxed) >= 1);
+
+    let (mut x, mut y) = (0u64, 1u64);
+    for _ in 0..steps {
+        let next = x.wrapping_add(y);
+        x = y;
+        y = next;
+    }
+    assert_eq!((a, b), (x, y));
+    println!("Test alarm handler OK!");
+    0
+}
@@ -0,0 +1,16 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+/// 正确输出：
+/// [kernel] Illegal instruction in application `...` at ..., kernel killed it.
+/// 其余应用正常运行
+
+#[no_mangle]
+pub fn main() -> i32 {
+    unsafe {
+        core::arch::asm!("csrw sscratch, zero");
+    }
+    panic!("FAIL: T.T\n");
+}
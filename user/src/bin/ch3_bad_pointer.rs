@@ -0,0 +1,33 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sys_get_time, syscall, TimeVal, SYSCALL_GETTIMEOFDAY, SYSCALL_TASK_INFO, SYSCALL_WRITE};
+
+/// 正确输出：（无报错信息）
+/// Test bad pointer OK!
+
+/// Where the kernel is loaded, far outside of any application.
+const KERNEL_ADDR: usize = 0x80200000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let before = unsafe { (KERNEL_ADDR as *const [usize; 2]).read_volatile() };
+
+    assert_eq!(-1, syscall(SYSCALL_GETTIMEOFDAY, [KERNEL_ADDR, 0, 0]));
+    assert_eq!(-1, syscall(SYSCALL_TASK_INFO, [KERNEL_ADDR, 0, 0]));
+    assert_eq!(-1, syscall(SYSCALL_WRITE, [1, KERNEL_ADDR, 16]));
+    // A buffer that starts inside the task but runs past its end.
+    assert_eq!(-1, syscall(SYSCALL_WRITE, [1, main as usize, usize::MAX]));
+
+    let after = unsafe { (KERNEL_ADDR as *const [usize; 2]).read_volatile() };
+    assert_eq!(before, after);
+
+    // Pointers into the task itself still work.
+    let time = TimeVal::new();
+    assert_eq!(0, sys_get_time(&time, 0));
+    println!("Test bad pointer OK!");
+    0
+}
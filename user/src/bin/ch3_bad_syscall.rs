@@ -0,0 +1,16 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+use user_lib::syscall;
+
+/// 正确输出：
+/// [kernel] Unsupported syscall `9999` in application, kernel killed it.
+/// 其余应用正常运行
+
+#[no_mangle]
+pub fn main() -> i32 {
+    syscall(9999, [0, 0, 0]);
+    panic!("FAIL: T.T\n");
+}
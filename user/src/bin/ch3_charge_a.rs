@@ -0,0 +1,30 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{find_task, sys_send, task_info, yield_, TaskInfo};
+
+/// 正确输出：（无报错信息，与 ch3_charge_b 配合）
+/// charge a done
+
+/// The same in ch3_charge_b.
+const WORK: usize = 2_000_000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut x = 0usize;
+    for i in 0..WORK {
+        x = unsafe { core::ptr::read_volatile(&x) }.wrapping_add(i);
+    }
+    // `cpu_clocks` is brought up to date when the task is switched away.
+    yield_();
+
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    let peer = find_task("ch3_charge_b").expect("ch3_charge_b isn't loaded");
+    assert_eq!(0, sys_send(peer, info.cpu_clocks));
+    println!("charge a done");
+    0
+}
@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sys_recv, task_info, yield_, TaskInfo};
+
+/// 正确输出：（无报错信息，与 ch3_charge_a 配合）
+/// Test fair charge OK!
+
+/// The same in ch3_charge_a.
+const WORK: usize = 2_000_000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut x = 0usize;
+    for i in 0..WORK {
+        x = unsafe { core::ptr::read_volatile(&x) }.wrapping_add(i);
+    }
+    yield_();
+
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    let mine = info.cpu_clocks;
+    // ch3_charge_a is loaded, and first scheduled, before this one. Only
+    // what each ran should count, not when it started.
+    let mut theirs = 0;
+    assert_eq!(0, sys_recv(&mut theirs));
+    assert!(mine > 0 && theirs > 0);
+    assert!(theirs <= mine * 2 && mine <= theirs * 2, "charged {} and {} for the same work", theirs, mine);
+    println!("Test fair charge OK!");
+    0
+}
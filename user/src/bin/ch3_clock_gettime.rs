@@ -0,0 +1,42 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sleep_blocking, sys_clock_gettime, syscall, TimeSpec, SYSCALL_CLOCK_GETTIME};
+
+/// 正确输出：（无报错信息）
+/// Test clock_gettime OK!
+
+const CLOCK_MONOTONIC: usize = 1;
+const NANO_PER_SEC: usize = 1000000000;
+const DELAY_MS: usize = 50;
+
+fn now_ns() -> usize {
+    let time = TimeSpec::new();
+    assert_eq!(0, sys_clock_gettime(CLOCK_MONOTONIC, &time));
+    assert!(time.nsec < NANO_PER_SEC);
+    time.sec * NANO_PER_SEC + time.nsec
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    assert_eq!(-1, syscall(SYSCALL_CLOCK_GETTIME, [CLOCK_MONOTONIC, 0, 0]));
+
+    let start = now_ns();
+    sleep_blocking(DELAY_MS);
+    let elapsed = now_ns() - start;
+    // At least the delay, and not absurdly more even with other apps running.
+    assert!(elapsed >= DELAY_MS * 1000000, "slept only {} ns", elapsed);
+    assert!(elapsed < 100 * DELAY_MS * 1000000, "slept {} ns", elapsed);
+
+    // Finer than microseconds.
+    let mut sub_micro = false;
+    for _ in 0..100 {
+        sub_micro |= now_ns() % 1000 != 0;
+    }
+    assert!(sub_micro);
+    println!("Test clock_gettime OK!");
+    0
+}
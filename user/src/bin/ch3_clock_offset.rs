@@ -0,0 +1,44 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, sys_get_clock_freq, task_info, yield_, TaskInfo};
+
+/// 正确输出：（无报错信息）
+/// Test clock offset OK!
+///
+/// Meant to be run with the kernel built with e.g.
+/// `CLOCK_OFFSET=4294000000`, so that the clock count goes past 32 bits
+/// while the task runs. Without it the same checks hold trivially.
+
+const SPIN_MS: isize = 100;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let freq = sys_get_clock_freq() as u128;
+    assert!(freq > 0);
+    let start = get_time();
+    if start as u128 * freq / 1000 > u32::MAX as u128 {
+        println!("the clock is past 32 bits");
+    }
+    while get_time() < start + SPIN_MS {
+        yield_();
+    }
+    // `cpu_clocks` is brought up to date when the task is switched away.
+    yield_();
+    let elapsed_ms = (get_time() - start) as usize;
+
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    // A count that wrapped while the task ran would come out either
+    // ridiculously large or as nothing at all.
+    assert!(info.cpu_clocks > 0);
+    assert!(info.cpu_time_ms <= elapsed_ms + 1);
+    assert_eq!(info.cpu_time_ms as u128, info.cpu_clocks as u128 * 1000 / freq);
+    assert!(info.time >= SPIN_MS as usize);
+    assert!(info.time <= elapsed_ms + 1000);
+    println!("Test clock offset OK!");
+    0
+}
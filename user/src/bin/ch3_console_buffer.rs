@@ -0,0 +1,42 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{write, STDOUT};
+
+/// 正确输出：（无报错信息，每行内容完整、顺序正确）
+/// 0123456789012345...（共 300 个数字，一行）
+/// split across three writes
+/// to stderr between lines
+/// Test console buffer OK!
+///
+/// The kernel buffers console output by line. Output must come out exactly
+/// as written, including lines longer than its 256-byte buffer.
+
+const STDERR: usize = 2;
+const LONG_LINE: usize = 300;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Longer than the kernel's line buffer, so it's flushed before the end.
+    let mut line = [0u8; LONG_LINE + 1];
+    for (i, c) in line[..LONG_LINE].iter_mut().enumerate() {
+        *c = b'0' + (i % 10) as u8;
+    }
+    line[LONG_LINE] = b'\n';
+    assert_eq!(line.len() as isize, write(STDOUT, &line));
+
+    // Partial lines are held until the newline.
+    for part in [&b"split across "[..], b"three ", b"writes\n"] {
+        assert_eq!(part.len() as isize, write(STDOUT, part));
+    }
+
+    // stderr isn't buffered, and must not overtake pending stdout output.
+    assert_eq!(3, write(STDOUT, b"to "));
+    let msg = b"stderr between lines\n";
+    assert_eq!(msg.len() as isize, write(STDERR, msg));
+    println!("Test console buffer OK!");
+    0
+}
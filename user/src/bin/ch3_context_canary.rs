@@ -0,0 +1,23 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sys_clobber_context_canary, yield_};
+
+/// 正确输出：（内核启用 context-canary 和 fault-injection 时打印以下信息后关机，否则跳过）
+/// panic in file `src/task.rs` at line ...: context of task `...` is corrupted, canary 0x... instead of 0xc0de5a5a
+/// ...
+
+#[no_mangle]
+pub fn main() -> i32 {
+    if sys_clobber_context_canary() != 0 {
+        println!("sys_clobber_context_canary is disabled, skipped");
+        return 0;
+    }
+    // Doesn't return: the switch away from this task checks its context.
+    yield_();
+    println!("FAIL: the kernel switched with a corrupted context");
+    0
+}
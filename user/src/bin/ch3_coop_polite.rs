@@ -0,0 +1,32 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, yield_};
+
+/// 正确输出：（无报错信息，与 ch3_coop_spinner 同时运行）
+/// longest wait for the CPU: ... ms
+/// Test coop polite OK!
+///
+/// The wait stays around a time slice with preemption, and grows to the
+/// spinner's whole run with `make run FEATURES=cooperative`.
+
+const RUN_MS: isize = 1500;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let start = get_time();
+    let mut last = start;
+    let mut longest_wait = 0;
+    while last - start < RUN_MS {
+        yield_();
+        let now = get_time();
+        longest_wait = longest_wait.max(now - last);
+        last = now;
+    }
+    println!("longest wait for the CPU: {} ms", longest_wait);
+    println!("Test coop polite OK!");
+    0
+}
@@ -0,0 +1,23 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::get_time;
+
+/// 正确输出：（无报错信息）
+/// Test coop spinner OK!
+///
+/// Never yields. Without preemption nothing else runs until it exits, see
+/// ch3_coop_polite.
+
+const SPIN_MS: isize = 1000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let start = get_time();
+    while get_time() - start < SPIN_MS {}
+    println!("Test coop spinner OK!");
+    0
+}
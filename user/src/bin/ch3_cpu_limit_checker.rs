@@ -0,0 +1,30 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{find_task, sys_task_info_of, yield_, TaskInfo, TaskStatus};
+
+/// 正确输出：（无报错信息）
+/// Test cpu limit OK!
+
+const SPINNER: &str = "ch3_cpu_limit_spinner";
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let spinner = find_task(SPINNER).expect("ch3_cpu_limit_spinner isn't loaded");
+    // Peek at the exit code without reaping the spinner.
+    let info = loop {
+        let info = TaskInfo::new();
+        assert_eq!(0, sys_task_info_of(spinner, &info));
+        if info.status == TaskStatus::Zombie {
+            break info;
+        }
+        yield_();
+    };
+    // Killed, or exited normally if nothing could preempt it.
+    assert!(info.exit_code == -1 || info.exit_code == 0);
+    println!("Test cpu limit OK!");
+    0
+}
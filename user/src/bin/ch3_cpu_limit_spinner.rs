@@ -0,0 +1,30 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, sys_get_clock_freq, sys_set_cpu_limit, sys_trap_stats, TrapStats};
+
+/// 正确输出：
+/// [kernel] Application `..` is over its CPU budget, kernel killed it.
+/// 其余应用正常运行，见 ch3_cpu_limit_checker
+
+const LIMIT_MS: usize = 50;
+const SPIN_MS: isize = 1000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let clocks_per_ms = sys_get_clock_freq() as usize / 1000;
+    assert_eq!(0, sys_set_cpu_limit(LIMIT_MS * clocks_per_ms));
+    let start = get_time();
+    while get_time() - start < SPIN_MS {}
+
+    // The budget is enforced on timer interrupts, which never come without
+    // preemption.
+    let stats = TrapStats::new();
+    assert_eq!(0, sys_trap_stats(&stats));
+    assert_eq!(0, stats.timer_interrupts, "FAIL: not killed over budget");
+    println!("Preemption is disabled, spinner wasn't killed");
+    0
+}
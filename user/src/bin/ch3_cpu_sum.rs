@@ -0,0 +1,38 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::vec;
+use user_lib::{
+    sleep_blocking, sys_get_task_count, sys_get_ticks, sys_idle_time, sys_list_tasks, TaskBrief,
+};
+
+/// 正确输出：（无报错信息）
+/// cpu ... + idle ... of ... clocks since boot
+/// Test cpu clock sum OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Let the other tasks run for a while, so boot is a small part of it.
+    sleep_blocking(500);
+
+    let mut briefs = vec![TaskBrief::new(); sys_get_task_count() as usize];
+    let idle_before = sys_idle_time() as usize;
+    let count = sys_list_tasks(&mut briefs);
+    let now = sys_get_ticks() as usize;
+    let idle_after = sys_idle_time() as usize;
+    assert!(count > 0);
+    let cpu: usize = briefs[..count as usize].iter().map(|brief| brief.cpu_clocks).sum();
+    println!("cpu {} + idle {} of {} clocks since boot", cpu, idle_before, now);
+
+    // Every clock is counted once at most, to one task or to idling.
+    assert!(cpu + idle_before <= now);
+    // Most of them are counted. Boot, the switches themselves and earlier
+    // runs of respawned tasks aren't.
+    assert!(cpu + idle_after >= now / 2);
+    println!("Test cpu clock sum OK!");
+    0
+}
@@ -0,0 +1,30 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, sys_get_clock_freq, task_info, yield_, TaskInfo};
+
+/// 正确输出：（无报错信息）
+/// Test cpu time OK!
+
+const SPIN_MS: isize = 50;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let start = get_time();
+    while get_time() < start + SPIN_MS {}
+    // `cpu_clocks` is brought up to date when the task is switched away.
+    yield_();
+
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    let freq = sys_get_clock_freq() as u128;
+    assert!(freq > 0);
+    // Both come from the same clock count.
+    assert_eq!(info.cpu_time_ms as u128, info.cpu_clocks as u128 * 1000 / freq);
+    assert!(info.cpu_clocks > 0);
+    println!("Test cpu time OK!");
+    0
+}
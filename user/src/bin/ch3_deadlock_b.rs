@@ -0,0 +1,25 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sys_signal_event, sys_wait_event};
+
+/// 正确输出：（与 ch3_deadlock_a 互相等待，其余应用结束后内核报告死锁并关机）
+/// [ WARN][task ...] deadlock: all tasks blocked
+/// [ WARN][task ...]   `ch3_deadlock_a` is blocked
+/// [ WARN][task ...]   `ch3_deadlock_b` is blocked
+
+/// Events 2 and 3 belong to this pair of tests.
+const MINE: usize = 3;
+const THEIRS: usize = 2;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // ch3_deadlock_a only signals once it has been signaled itself.
+    sys_wait_event(MINE);
+    sys_signal_event(THEIRS);
+    println!("FAIL: ch3_deadlock_b woke up");
+    0
+}
@@ -0,0 +1,53 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{getpid, sys_get_clock_freq, sys_get_task_count, sys_task_info_of, yield_, TaskInfo};
+
+/// 正确输出：（无报错信息）
+/// Test dispatch latency OK!
+///
+/// All apps are loaded together at boot and first dispatched in load
+/// order, so the later a task is dispatched the longer it waited.
+
+/// Tasks loaded this close to this one count as loaded with it, rather
+/// than spawned later.
+const SAME_LOAD_MS: usize = 5;
+
+/// The latency of `tid` and how long ago it was loaded, if it has run.
+fn latency_and_age(tid: usize, clocks_per_ms: usize) -> Option<(usize, usize)> {
+    let info = TaskInfo::new();
+    if sys_task_info_of(tid, &info) != 0 || info.schedule_count == 0 {
+        return None;
+    }
+    Some((info.dispatch_latency, info.time + info.dispatch_latency / clocks_per_ms))
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let task_count = sys_get_task_count() as usize;
+    // Let later tasks get their first turn.
+    for _ in 0..task_count {
+        yield_();
+    }
+    let clocks_per_ms = sys_get_clock_freq() as usize / 1000;
+    let pid = getpid() as usize;
+    let (latency, age) = latency_and_age(pid, clocks_per_ms).unwrap();
+    for tid in 0..task_count {
+        let other_latency = match latency_and_age(tid, clocks_per_ms) {
+            Some((other_latency, other_age)) if other_age.abs_diff(age) <= SAME_LOAD_MS => {
+                other_latency
+            }
+            _ => continue,
+        };
+        if tid < pid {
+            assert!(other_latency <= latency, "task {} dispatched after {}", tid, pid);
+        } else if tid > pid {
+            assert!(other_latency >= latency, "task {} dispatched before {}", tid, pid);
+        }
+    }
+    println!("Test dispatch latency OK!");
+    0
+}
@@ -0,0 +1,21 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::sys_dump_regs;
+
+/// 正确输出：（无报错信息，寄存器的值因运行而异）
+/// [kernel] registers of application `...`:
+/// ...
+/// Test dump regs OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Unlike a fault, the task is not killed, and may ask again.
+    assert_eq!(0, sys_dump_regs());
+    assert_eq!(0, sys_dump_regs());
+    println!("Test dump regs OK!");
+    0
+}
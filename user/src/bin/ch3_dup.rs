@@ -0,0 +1,39 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{dup, read, write};
+
+/// 正确输出：（无报错信息）
+/// written through fd 4
+/// Test dup OK!
+
+const STDIN: usize = 0;
+const STDOUT: usize = 1;
+const FD_TABLE_SIZE: usize = 8;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // 0 to 3 are open from the start, 4 not yet.
+    assert_eq!(-1, write(4, b"unreachable\n"));
+    assert_eq!(-1, dup(4));
+
+    let fd = dup(STDOUT);
+    assert_eq!(4, fd);
+    let msg = b"written through fd 4\n";
+    assert_eq!(msg.len() as isize, write(fd as usize, msg));
+    // Still stdout, not something to read from.
+    let mut buf = [0u8; 1];
+    assert_eq!(-1, read(fd as usize, &mut buf));
+
+    // Always the lowest free fd, until the table is full.
+    for expected in 5..FD_TABLE_SIZE {
+        assert_eq!(expected as isize, dup(STDIN));
+    }
+    assert_eq!(-1, dup(STDIN));
+    assert_eq!(-1, dup(usize::MAX));
+    println!("Test dup OK!");
+    0
+}
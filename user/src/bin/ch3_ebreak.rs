@@ -0,0 +1,25 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::arch::asm;
+
+/// 正确输出：
+/// [kernel] Breakpoint in application `...` at 0x..., resuming.
+/// [kernel] Breakpoint in application `...` at 0x..., resuming.
+/// Test ebreak OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    unsafe {
+        // `ebreak`, spelled out so that it isn't compressed.
+        asm!(".4byte 0x00100073");
+        // `c.ebreak`
+        asm!(".2byte 0x9002");
+    }
+    // Skipping the wrong length would have crashed before getting here.
+    println!("Test ebreak OK!");
+    0
+}
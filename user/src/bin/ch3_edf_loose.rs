@@ -0,0 +1,54 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    find_task, get_time, sys_get_clock_freq, sys_set_deadline, sys_task_info_of, yield_, TaskInfo,
+    TaskStatus, SYSCALL_SET_DEADLINE,
+};
+
+/// 正确输出：（无报错信息，晚于 ch3_edf_tight 完成）
+/// Test edf loose OK!
+
+const TIGHT: &str = "ch3_edf_tight";
+/// Far later than any deadline of `ch3_edf_tight`.
+const DEADLINE_MS: usize = 2000;
+const RUN_MS: isize = 300;
+
+fn task_info_of(tid: usize) -> TaskInfo {
+    let info = TaskInfo::new();
+    assert_eq!(0, sys_task_info_of(tid, &info));
+    info
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let tight = find_task(TIGHT).expect("ch3_edf_tight isn't loaded");
+    // Without a deadline of its own yet, the tight task would sort last.
+    while task_info_of(tight).syscall_times[SYSCALL_SET_DEADLINE] == 0 {
+        yield_();
+    }
+
+    let clocks_per_ms = sys_get_clock_freq() as usize / 1000;
+    if sys_set_deadline(DEADLINE_MS * clocks_per_ms) != 0 {
+        // Only `make run FEATURES=edf` schedules by deadline.
+        println!("EDF is disabled");
+        println!("Test edf loose OK!");
+        return 0;
+    }
+
+    // Whenever the tight task is runnable it has the nearer deadline, so it
+    // is never left waiting while this one runs.
+    let start = get_time();
+    while get_time() - start < RUN_MS {
+        let info = task_info_of(tight);
+        assert_ne!(TaskStatus::Ready, info.status);
+        yield_();
+    }
+    let info = task_info_of(tight);
+    assert!(matches!(info.status, TaskStatus::Zombie | TaskStatus::Exited));
+    println!("Test edf loose OK!");
+    0
+}
@@ -0,0 +1,32 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, sleep_blocking, sys_get_clock_freq, sys_set_deadline};
+
+/// 正确输出：（无报错信息，先于 ch3_edf_loose 完成）
+/// Test edf tight OK!
+
+const PERIODS: usize = 5;
+const PERIOD_MS: usize = 20;
+const WORK_MS: isize = 5;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let clocks_per_ms = sys_get_clock_freq() as usize / 1000;
+    for _ in 0..PERIODS {
+        // Each period has to be done by its end.
+        if sys_set_deadline(PERIOD_MS * clocks_per_ms) != 0 {
+            // Only `make run FEATURES=edf` schedules by deadline.
+            println!("EDF is disabled");
+            break;
+        }
+        let start = get_time();
+        while get_time() - start < WORK_MS {}
+        sleep_blocking(PERIOD_MS - WORK_MS as usize);
+    }
+    println!("Test edf tight OK!");
+    0
+}
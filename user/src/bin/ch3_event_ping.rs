@@ -0,0 +1,36 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sys_signal_event, sys_wait_event, yield_};
+
+/// 正确输出：（无报错信息，与 ch3_event_pong 配合）
+/// Test event handshake OK!
+
+/// Events 0 and 1 belong to this pair of tests.
+const PING: usize = 0;
+const PONG: usize = 1;
+const ROUNDS: usize = 10;
+
+/// Signal `event` once somebody waits on it. Events are edge-triggered, so
+/// an earlier signal would be lost.
+fn signal_waiter(event: usize) {
+    while sys_signal_event(event) == 0 {
+        yield_();
+    }
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    assert_eq!(-1, sys_wait_event(8));
+    assert_eq!(-1, sys_signal_event(usize::MAX));
+
+    for _ in 0..ROUNDS {
+        signal_waiter(PING);
+        assert_eq!(0, sys_wait_event(PONG));
+    }
+    println!("Test event handshake OK!");
+    0
+}
@@ -0,0 +1,30 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sys_signal_event, sys_wait_event, yield_};
+
+/// 正确输出：（无报错信息，与 ch3_event_ping 配合）
+/// event pong done
+
+const PING: usize = 0;
+const PONG: usize = 1;
+const ROUNDS: usize = 10;
+
+fn signal_waiter(event: usize) {
+    while sys_signal_event(event) == 0 {
+        yield_();
+    }
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    for _ in 0..ROUNDS {
+        assert_eq!(0, sys_wait_event(PING));
+        signal_waiter(PONG);
+    }
+    println!("event pong done");
+    0
+}
@@ -0,0 +1,15 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+/// 正确输出：（无报错信息）
+/// exit with code 42
+/// 由 ch3_exit_code_reader 读回退出码
+
+#[no_mangle]
+pub fn main() -> i32 {
+    println!("exit with code 42");
+    42
+}
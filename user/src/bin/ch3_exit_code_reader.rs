@@ -0,0 +1,36 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    sys_get_task_count, sys_task_info_of, sys_waittid_exit_code, yield_, TaskInfo, TaskStatus,
+};
+
+/// 正确输出：（无报错信息）
+/// Test exit code OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Look for whichever task exited with 42, without reaping the zombies of
+    // other tests.
+    loop {
+        for tid in 0..sys_get_task_count() as usize {
+            let info = TaskInfo::new();
+            if sys_task_info_of(tid, &info) != 0
+                || info.status != TaskStatus::Zombie
+                || info.exit_code != 42
+            {
+                continue;
+            }
+            let mut exit_code = 0;
+            assert_eq!(0, sys_waittid_exit_code(tid, &mut exit_code));
+            assert_eq!(42, exit_code);
+            println!("task {} exited with code {}", tid, exit_code);
+            println!("Test exit code OK!");
+            return 0;
+        }
+        yield_();
+    }
+}
@@ -0,0 +1,18 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::exit;
+
+/// 正确输出：（无报错信息，且下面这行出现在输出中，可能与其他输出同行）
+/// Test exit flush OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // No trailing newline: `exit` hands it to the kernel, whose console
+    // buffer still holds it when the task exits.
+    print!("Test exit flush OK!");
+    exit(0);
+}
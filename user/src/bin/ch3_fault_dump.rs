@@ -0,0 +1,26 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+/// 正确输出：
+/// [kernel] Store/AMO access fault in application `...`, bad addr = 0x0, kernel killed it.
+/// 随后是寄存器表，其中包含：
+///   a0: 0x0000000000005eed    a1: 0x00000000000000a1
+/// scause: 0x0000000000000007 (Store/AMO access fault)  stval: 0x0000000000000000
+/// 其余应用正常运行
+
+#[no_mangle]
+pub fn main() -> i32 {
+    unsafe {
+        // Marks in a0 and a1 to look for in the dump.
+        core::arch::asm!(
+            "li a0, 0x5eed",
+            "li a1, 0xa1",
+            "sd zero, 0(zero)",
+            out("a0") _,
+            out("a1") _,
+        );
+    }
+    panic!("FAIL: T.T\n");
+}
@@ -0,0 +1,61 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::vec;
+use user_lib::{
+    getpid, set_priority, sys_get_task_count, sys_task_info_of, task_info, yield_, TaskInfo,
+    TaskStatus,
+};
+
+/// 正确输出：（无报错信息）
+/// Test fifo order OK!
+///
+/// With the lowest priority, every task that is ready when this one yields
+/// is at least as preferable, so all of them must run before it does again.
+
+/// The kernel's `MIN_PRIORITY`.
+const MIN_PRIORITY: isize = 2;
+const ROUNDS: usize = 10;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let pid = getpid() as usize;
+    let task_count = sys_get_task_count() as usize;
+    assert_eq!(MIN_PRIORITY, set_priority(MIN_PRIORITY));
+    for _ in 0..ROUNDS {
+        let me = TaskInfo::new();
+        assert_eq!(0, task_info(&me));
+        // Ready tasks queued ahead of this one, and how often they had run.
+        let mut ahead = vec![None; task_count];
+        for (tid, entry) in ahead.iter_mut().enumerate() {
+            let info = TaskInfo::new();
+            // Under MLFQ, tasks at lower levels wait for this one.
+            if tid != pid
+                && sys_task_info_of(tid, &info) == 0
+                && info.status == TaskStatus::Ready
+                && info.mlfq_level <= me.mlfq_level
+            {
+                *entry = Some(info.schedule_count);
+            }
+        }
+        yield_();
+        for (tid, entry) in ahead.iter().enumerate() {
+            if let Some(schedule_count) = *entry {
+                let info = TaskInfo::new();
+                // Reaped tasks ran, or they couldn't have exited.
+                if sys_task_info_of(tid, &info) == 0 {
+                    assert!(
+                        info.schedule_count > schedule_count,
+                        "task {} was ready first but hasn't run", tid
+                    );
+                }
+            }
+        }
+    }
+    println!("Test fifo order OK!");
+    0
+}
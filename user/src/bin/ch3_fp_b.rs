@@ -0,0 +1,52 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::arch::asm;
+use user_lib::SYSCALL_YIELD;
+
+/// 正确输出：（无报错信息，与 ch3_fp_a 同时运行）
+/// Test fp b OK!
+
+const START: f64 = -1000000.0;
+/// Rounding mode kept in `fcsr`, distinct from ch3_fp_a.
+const ROUNDING_MODE: usize = 2;
+const ROUNDS: usize = 200;
+
+/// Yield with `value` held in `ft0` and `ROUNDING_MODE` in `frm`, and
+/// return what they hold once the task is back.
+fn yield_holding(value: f64) -> (f64, usize) {
+    let bits: u64;
+    let frm: usize;
+    unsafe {
+        asm!(
+            "fmv.d.x ft0, {bits}",
+            "fsrm {frm}",
+            "ecall",
+            "fmv.x.d {bits}, ft0",
+            "frrm {frm}",
+            bits = inout(reg) value.to_bits() => bits,
+            frm = inout(reg) ROUNDING_MODE => frm,
+            inlateout("x10") 0usize => _,
+            in("x17") SYSCALL_YIELD,
+            out("ft0") _,
+        );
+    }
+    (f64::from_bits(bits), frm)
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut value = START;
+    for i in 0..ROUNDS {
+        let (held, frm) = yield_holding(value);
+        assert_eq!(value, held, "round {}", i);
+        assert_eq!(ROUNDING_MODE, frm, "round {}", i);
+        value += 1.0;
+    }
+    assert_eq!(START + ROUNDS as f64, value);
+    println!("Test fp b OK!");
+    0
+}
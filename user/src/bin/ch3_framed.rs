@@ -0,0 +1,25 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::write;
+
+/// 正确输出：（无报错信息，两帧按字节输出，以 hexdump 查看）
+/// f5 05 00 00 00 68 65 6c 6c 6f                    |.....hello|
+/// f5 04 00 00 00 00 ff 0a 01                       |.........|
+/// Test framed output OK!
+
+const FRAMED: usize = 3;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Each write is one frame: 0xf5, the length as a little-endian u32,
+    // then the bytes as they are, whatever they are.
+    assert_eq!(5, write(FRAMED, b"hello"));
+    assert_eq!(4, write(FRAMED, &[0x00, 0xff, b'\n', 0x01]));
+    println!("");
+    println!("Test framed output OK!");
+    0
+}
@@ -0,0 +1,31 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{syscall, sys_get_time, TimeVal, SYSCALL_GETTIMEOFDAY};
+
+/// 正确输出：（无报错信息）
+/// Test get_time OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    assert_eq!(-1, syscall(SYSCALL_GETTIMEOFDAY, [0, 0, 0]));
+
+    let mut sub_milli = false;
+    let mut last = 0;
+    for _ in 0..100 {
+        let time = TimeVal::new();
+        assert_eq!(0, sys_get_time(&time, 0));
+        assert!(time.usec < 1000000);
+        let now = time.sec * 1000000 + time.usec;
+        assert!(now >= last);
+        last = now;
+        sub_milli |= time.usec % 1000 != 0;
+    }
+    // The time has microsecond precision rather than whole milliseconds.
+    assert!(sub_milli);
+    println!("Test get_time OK!");
+    0
+}
@@ -0,0 +1,26 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sys_get_time, task_info, TaskInfo, TimeVal, SYSCALL_GETTIMEOFDAY};
+
+/// 正确输出：（无报错信息）
+/// Test get_time twice OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let first = TimeVal::new();
+    let second = TimeVal::new();
+    assert_eq!(0, sys_get_time(&first, 0));
+    assert_eq!(0, sys_get_time(&second, 0));
+    // Reaching here at all means neither `ecall` was re-executed forever.
+    assert!((second.sec, second.usec) >= (first.sec, first.usec));
+
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    assert_eq!(2, info.syscall_times[SYSCALL_GETTIMEOFDAY]);
+    println!("Test get_time twice OK!");
+    0
+}
@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{getpid, task_info, TaskInfo, SYSCALL_GETPID};
+
+/// 正确输出：（无报错信息）
+/// pid = ...
+/// Test getpid OK!
+
+/// Where the kernel loads task 0, and how far apart tasks are loaded.
+const APP_BASE_ADDR: usize = 0x80400000;
+const MAX_APP_SIZE: usize = 0x20000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let pid = getpid();
+    assert!(pid >= 0);
+    assert_eq!(pid, getpid());
+
+    // Each task is loaded into its own slot, so a pid matching the slot we
+    // run in can't be shared with another app.
+    let slot = (main as usize - APP_BASE_ADDR) / MAX_APP_SIZE;
+    assert_eq!(pid as usize, slot);
+
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    assert_eq!(2, info.syscall_times[SYSCALL_GETPID]);
+    println!("pid = {}", pid);
+    println!("Test getpid OK!");
+    0
+}
@@ -0,0 +1,41 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::sys_getrandom;
+
+/// 正确输出：（无报错信息）
+/// Test getrandom OK!
+
+const LEN: usize = 4096;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut buf = [0u8; LEN];
+    assert_eq!(LEN as isize, sys_getrandom(&mut buf));
+    assert_eq!(0, sys_getrandom(&mut []));
+    // Kernel memory isn't the caller's to fill.
+    let kernel = unsafe { core::slice::from_raw_parts_mut(0x8020_0000 as *mut u8, 16) };
+    assert_eq!(-1, sys_getrandom(kernel));
+
+    // About half the bits are set.
+    let ones: u32 = buf.iter().map(|byte| byte.count_ones()).sum();
+    let bits = (LEN * 8) as u32;
+    assert!(ones > bits * 45 / 100 && ones < bits * 55 / 100, "{} of {} bits set", ones, bits);
+
+    // Each high nibble comes up about equally often, 256 times on average.
+    let mut counts = [0u32; 16];
+    buf.iter().for_each(|&byte| counts[(byte >> 4) as usize] += 1);
+    for (nibble, &count) in counts.iter().enumerate() {
+        assert!(count > 160 && count < 352, "nibble {:x} came up {} times", nibble, count);
+    }
+
+    // The stream goes on rather than starting over.
+    let mut next = [0u8; LEN];
+    assert_eq!(LEN as isize, sys_getrandom(&mut next));
+    assert_ne!(buf, next);
+    println!("Test getrandom OK!");
+    0
+}
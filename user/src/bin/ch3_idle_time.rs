@@ -0,0 +1,27 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sleep_blocking, sys_idle_time};
+
+/// 正确输出：（无报错信息）
+/// Test idle time OK!
+
+/// The CPU is only idle once every other task sleeps or has exited too,
+/// so keep sleeping until that happens.
+const MAX_NAPS: usize = 500;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let before = sys_idle_time();
+    assert!(before >= 0);
+    let idled = (0..MAX_NAPS).any(|_| {
+        sleep_blocking(10);
+        sys_idle_time() > before
+    });
+    assert!(idled, "the CPU never idled while sleeping");
+    println!("Test idle time OK!");
+    0
+}
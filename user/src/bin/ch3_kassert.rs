@@ -0,0 +1,28 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::sys_kernel_assert;
+
+/// 正确输出：（内核启用 fault-injection 时打印以下信息后关机，否则跳过）
+/// [kernel] assertion failed in file `src/syscall.rs` at line ...: sys_kernel_assert(42)
+/// [kernel] current task: ...
+/// [kernel] registers it last trapped with:
+/// zero: 0x0000000000000000    ra: ...
+/// ...    a0: 0x000000000000002a ...
+/// ...
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let enabled = sys_kernel_assert(0) == 0;
+    if !enabled {
+        println!("sys_kernel_assert is disabled, skipped");
+        return 0;
+    }
+    // Doesn't return: a0 = 42 shows up in the registers printed.
+    sys_kernel_assert(42);
+    println!("FAIL: the kernel survived a failed kassert");
+    0
+}
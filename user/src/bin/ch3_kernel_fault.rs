@@ -0,0 +1,21 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::sys_kernel_fault;
+
+/// 正确输出：（内核启用 fault-injection 时打印以下信息后关机，否则跳过）
+/// [kernel] trap in kernel, registers:
+/// ...
+/// panic in file `src/trap.rs` at line ...: Load access fault in kernel at ..., stval = 0x0
+/// ...
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Doesn't return if the kernel has the feature.
+    assert_eq!(-1, sys_kernel_fault());
+    println!("sys_kernel_fault is disabled, skipped");
+    0
+}
@@ -0,0 +1,24 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{getpid, sys_kernel_panic};
+
+/// 正确输出：（内核启用 fault-injection 时打印以下信息后关机，否则跳过）
+/// task N panics the kernel
+/// panic in file `src/syscall.rs` at line ...: sys_kernel_panic
+/// current task `ch3_kernel_panic` (N, Running), sepc = 0x...
+/// ...
+///
+/// The report names the same task id N as printed first.
+
+#[no_mangle]
+pub fn main() -> i32 {
+    println!("task {} panics the kernel", getpid());
+    // Doesn't return if the kernel has the feature.
+    assert_eq!(-1, sys_kernel_panic());
+    println!("sys_kernel_panic is disabled, skipped");
+    0
+}
@@ -0,0 +1,24 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{getpid, sys_kernel_panic_locked};
+
+/// 正确输出：（内核启用 fault-injection 时打印以下信息后关机，否则跳过）
+/// task N panics the kernel with its task manager locked
+/// panic in file `src/syscall.rs` at line ...: sys_kernel_panic_locked
+/// current task `N` (task info unavailable: lock held), sepc = 0x...
+/// ...
+///
+/// The machine powers off rather than hang on the lock.
+
+#[no_mangle]
+pub fn main() -> i32 {
+    println!("task {} panics the kernel with its task manager locked", getpid());
+    // Doesn't return if the kernel has the feature.
+    assert_eq!(-1, sys_kernel_panic_locked());
+    println!("sys_kernel_panic_locked is disabled, skipped");
+    0
+}
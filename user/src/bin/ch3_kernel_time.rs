@@ -0,0 +1,27 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, task_info, TaskInfo};
+
+/// 正确输出：（无报错信息）
+/// Test kernel time OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    for _ in 0..10000 {
+        get_time();
+    }
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    assert!(info.kernel_clocks > 0);
+    assert!(info.user_clocks > 0);
+    println!(
+        "kernel clocks = {}, user clocks = {}",
+        info.kernel_clocks, info.user_clocks
+    );
+    println!("Test kernel time OK!");
+    0
+}
@@ -0,0 +1,45 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    find_task, sys_get_task_count, sys_kill, sys_task_info_of, yield_, TaskInfo, TaskStatus,
+};
+
+/// 正确输出：（无报错信息）
+/// Test kill OK!
+
+const SPINNER: &str = "ch3_kill_spinner";
+
+fn task_info_of(tid: usize) -> TaskInfo {
+    let info = TaskInfo::new();
+    assert_eq!(0, sys_task_info_of(tid, &info));
+    info
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    assert_eq!(-1, sys_kill(sys_get_task_count() as usize));
+    let spinner = find_task(SPINNER).expect("ch3_kill_spinner isn't loaded");
+    // Let it start spinning.
+    while task_info_of(spinner).schedule_count == 0 {
+        yield_();
+    }
+
+    assert_eq!(0, sys_kill(spinner));
+    let info = task_info_of(spinner);
+    assert_eq!(TaskStatus::Zombie, info.status);
+    assert_eq!(-1, info.exit_code);
+    // Already dead.
+    assert_eq!(-1, sys_kill(spinner));
+
+    // It is never scheduled again.
+    for _ in 0..20 {
+        yield_();
+        assert_eq!(info.schedule_count, task_info_of(spinner).schedule_count);
+    }
+    println!("Test kill OK!");
+    0
+}
@@ -0,0 +1,22 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, yield_};
+
+/// 正确输出：（无输出，被 ch3_kill_controller 杀死）
+
+const SPIN_MS: isize = 5000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Yield so that it is killed even without preemption.
+    let start = get_time();
+    while get_time() - start < SPIN_MS {
+        yield_();
+    }
+    println!("FAIL: spinner wasn't killed");
+    0
+}
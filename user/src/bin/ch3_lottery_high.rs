@@ -0,0 +1,26 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, sys_set_tickets};
+
+/// 正确输出：（无报错信息，占用约 ch3_lottery_low 四倍的 CPU 时间）
+/// Test lottery high OK!
+
+const TICKETS: usize = 400;
+const RUN_MS: isize = 2000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    if sys_set_tickets(TICKETS) != TICKETS as isize {
+        // Only `make run FEATURES=lottery` draws lots.
+        println!("Lottery scheduling is disabled");
+    } else {
+        let start = get_time();
+        while get_time() - start < RUN_MS {}
+    }
+    println!("Test lottery high OK!");
+    0
+}
@@ -0,0 +1,58 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    find_task, get_time, sys_set_tickets, sys_task_info_of, task_info, yield_, TaskInfo, TaskStatus,
+};
+
+/// 正确输出：（无报错信息）
+/// cpu clocks: high .., low .., ratio ..
+/// Test lottery low OK!
+///
+/// Spins over the same stretch of time as ch3_lottery_high, which holds four
+/// times the tickets and so should get about four times the CPU.
+
+const HIGH: &str = "ch3_lottery_high";
+const HIGH_TICKETS: usize = 400;
+const TICKETS: usize = 100;
+const RUN_MS: isize = 2000;
+
+fn cpu_clocks(info: &TaskInfo) -> usize {
+    info.user_clocks + info.kernel_clocks
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    if sys_set_tickets(TICKETS) != TICKETS as isize {
+        // Only `make run FEATURES=lottery` draws lots.
+        println!("Lottery scheduling is disabled");
+        println!("Test lottery low OK!");
+        return 0;
+    }
+    let high = find_task(HIGH).expect("ch3_lottery_high isn't loaded");
+    let start = get_time();
+    while get_time() - start < RUN_MS {}
+    let low_info = TaskInfo::new();
+    assert_eq!(0, task_info(&low_info));
+
+    let high_info = loop {
+        let info = TaskInfo::new();
+        assert_eq!(0, sys_task_info_of(high, &info));
+        if info.status == TaskStatus::Zombie {
+            break info;
+        }
+        yield_();
+    };
+    assert_eq!(HIGH_TICKETS, high_info.tickets);
+    assert_eq!(TICKETS, low_info.tickets);
+    let (high_clocks, low_clocks) = (cpu_clocks(&high_info), cpu_clocks(&low_info));
+    let ratio = high_clocks as f64 / low_clocks as f64;
+    println!("cpu clocks: high {}, low {}, ratio {:.2}", high_clocks, low_clocks, ratio);
+    // Expected 4, the draws are random.
+    assert!(ratio > 2.0 && ratio < 8.0);
+    println!("Test lottery low OK!");
+    0
+}
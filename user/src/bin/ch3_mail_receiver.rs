@@ -0,0 +1,24 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::sys_recv;
+
+/// 正确输出：（无报错信息，与 ch3_mail_sender 配合）
+/// Test mailbox OK!
+
+const MESSAGES: usize = 10;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    for i in 0..MESSAGES {
+        // Blocks until ch3_mail_sender gets to it.
+        let mut msg = 0;
+        assert_eq!(0, sys_recv(&mut msg));
+        assert_eq!(1000 + i, msg);
+    }
+    println!("Test mailbox OK!");
+    0
+}
@@ -0,0 +1,23 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{find_task, sys_get_task_count, sys_send};
+
+/// 正确输出：（无报错信息，与 ch3_mail_receiver 配合）
+/// mail sender done
+
+const MESSAGES: usize = 10;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    assert_eq!(-1, sys_send(sys_get_task_count() as usize, 0));
+    let receiver = find_task("ch3_mail_receiver").expect("receiver isn't loaded");
+    for i in 0..MESSAGES {
+        assert_eq!(0, sys_send(receiver, 1000 + i));
+    }
+    println!("mail sender done");
+    0
+}
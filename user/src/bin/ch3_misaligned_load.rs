@@ -0,0 +1,28 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+/// 正确输出：
+/// [kernel] Load address misaligned in application `...` at 0x..., bad addr = 0x..., kernel killed it.
+/// 其余应用正常运行
+///
+/// Many SBI implementations, OpenSBI included, emulate misaligned accesses
+/// in M-mode, in which case the load just works and the kernel never sees a
+/// fault.
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let bytes = [0x11u8, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88];
+    let addr = bytes.as_ptr() as usize + 1;
+    let value: u32;
+    unsafe {
+        // A plain `lw`, which the compiler wouldn't emit for an unaligned
+        // pointer.
+        core::arch::asm!("lw {}, 0({})", out(reg) value, in(reg) addr);
+    }
+    assert_eq!(0x5544_3322, value);
+    println!("Misaligned load emulated by the SBI, not killed");
+    0
+}
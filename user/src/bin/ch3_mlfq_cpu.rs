@@ -0,0 +1,42 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, getpid, sys_sched_getparam, task_info, SchedParam, TaskInfo};
+
+/// 正确输出：（无报错信息）
+/// Test mlfq cpu OK!
+
+/// The kernel's `MLFQ_LEVELS`.
+const MLFQ_LEVELS: usize = 3;
+/// `SchedParam::policy` of MLFQ.
+const POLICY_MLFQ: usize = 2;
+const SPIN_MS: isize = 300;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut param = SchedParam::default();
+    assert_eq!(0, sys_sched_getparam(getpid() as usize, &mut param));
+    let mlfq = param.policy == POLICY_MLFQ;
+
+    // Spin without yielding, watching the level drop as quanta are used up.
+    let mut max_level = 0;
+    let start = get_time();
+    while get_time() - start < SPIN_MS {
+        let info = TaskInfo::new();
+        assert_eq!(0, task_info(&info));
+        assert!(info.mlfq_level < MLFQ_LEVELS);
+        max_level = max_level.max(info.mlfq_level);
+    }
+    if mlfq {
+        assert_eq!(MLFQ_LEVELS - 1, max_level);
+    } else {
+        // Only MLFQ (`make run FEATURES=mlfq`) moves tasks between levels.
+        assert_eq!(0, max_level);
+        println!("MLFQ is disabled, cpu-bound task stayed at level 0");
+    }
+    println!("Test mlfq cpu OK!");
+    0
+}
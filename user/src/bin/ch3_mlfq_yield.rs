@@ -0,0 +1,27 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, task_info, yield_, TaskInfo};
+
+/// 正确输出：（无报错信息）
+/// Test mlfq yield OK!
+
+const RUN_MS: isize = 300;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Always yield long before a quantum runs out, so the task is never
+    // demoted from the top level.
+    let start = get_time();
+    while get_time() - start < RUN_MS {
+        let info = TaskInfo::new();
+        assert_eq!(0, task_info(&info));
+        assert_eq!(0, info.mlfq_level);
+        yield_();
+    }
+    println!("Test mlfq yield OK!");
+    0
+}
@@ -0,0 +1,34 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sys_clock_gettime, sys_nanosleep, TimeSpec};
+
+/// 正确输出：（无报错信息）
+/// Test nanosleep OK!
+
+const NANO_PER_SEC: usize = 1_000_000_000;
+const SLEEP_NS: usize = 50_000_000;
+
+fn now_ns() -> usize {
+    let time = TimeSpec::new();
+    assert_eq!(0, sys_clock_gettime(0, &time));
+    time.sec * NANO_PER_SEC + time.nsec
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Not a valid duration.
+    let bad = TimeSpec { sec: 0, nsec: NANO_PER_SEC };
+    assert_eq!(-1, sys_nanosleep(&bad));
+
+    let start = now_ns();
+    let req = TimeSpec { sec: 0, nsec: SLEEP_NS };
+    assert_eq!(0, sys_nanosleep(&req));
+    let elapsed = now_ns() - start;
+    assert!(elapsed >= SLEEP_NS, "woke up after {} ns", elapsed);
+    println!("Test nanosleep OK!");
+    0
+}
@@ -0,0 +1,17 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+/// 正确输出：
+/// [kernel] Load page fault in application `...`, bad addr = 0x0, kernel killed it.
+/// 其余应用正常运行
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let value = unsafe {
+        #[allow(clippy::zero_ptr)]
+        (0x0 as *const u8).read_volatile()
+    };
+    panic!("FAIL: T.T, read {}\n", value);
+}
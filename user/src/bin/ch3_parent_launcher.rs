@@ -0,0 +1,40 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{find_task, get_time, sys_recv, sys_spawn_app, sys_waittid_exit_code, yield_};
+
+/// 正确输出：（无报错信息，与 ch3_parent_worker 配合）
+/// Test parent wake OK!
+
+/// Well under a time slice: any other task run in between would take more.
+const MAX_RESUME_MS: usize = 5;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let tid = find_task("ch3_parent_worker").expect("worker isn't loaded");
+    let mut exit_code = 0;
+    let mut exited_at = 0;
+
+    // Loaded at boot, so not our child: waiting doesn't block, it fails
+    // until there is something to reap.
+    while sys_waittid_exit_code(tid, &mut exit_code) != 0 {
+        yield_();
+    }
+    assert_eq!(7, exit_code);
+    assert_eq!(0, sys_recv(&mut exited_at));
+
+    // Now it is, and the wait ends as soon as it exits.
+    assert_eq!(tid as isize, sys_spawn_app(tid));
+    assert_eq!(0, sys_waittid_exit_code(tid, &mut exit_code));
+    let resumed_at = get_time() as usize;
+    assert_eq!(7, exit_code);
+    assert_eq!(0, sys_recv(&mut exited_at));
+    assert!(resumed_at - exited_at <= MAX_RESUME_MS, "resumed {} ms after the exit", resumed_at - exited_at);
+    // Reaped already.
+    assert_eq!(-1, sys_waittid_exit_code(tid, &mut exit_code));
+    println!("Test parent wake OK!");
+    0
+}
@@ -0,0 +1,25 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{find_task, get_time, sys_send, yield_};
+
+/// 正确输出：（无报错信息，与 ch3_parent_launcher 配合，运行两次）
+/// parent worker done
+
+const SPIN_MS: isize = 20;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let launcher = find_task("ch3_parent_launcher").expect("launcher isn't loaded");
+    // Long enough for the launcher to block waiting.
+    let start = get_time();
+    while get_time() < start + SPIN_MS {}
+    // A fresh time slice, so that nothing preempts it from here to the exit.
+    yield_();
+    assert_eq!(0, sys_send(launcher, get_time() as usize));
+    println!("parent worker done");
+    7
+}
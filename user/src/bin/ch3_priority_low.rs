@@ -0,0 +1,101 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+use user_lib::{
+    find_task, get_time, getpid, set_priority, sys_sched_getparam, sys_task_info_of, task_info,
+    yield_, SchedParam, TaskInfo, TaskStatus,
+};
+
+/*
+理想结果：ch3_priority_high 最先完成且 count 最大，ch3_priority_low 最后完成
+*/
+
+/// 正确输出：（无报错信息）
+/// priority = 4, count = .., finished at ..ms
+/// cpu clocks: high .., mid .., low ..
+/// Test priority OK!
+///
+/// Waits for ch3_priority_high and ch3_priority_mid to exit, and checks that
+/// the higher the priority, the more CPU a task got over the same stretch
+/// of time.
+
+const HIGH: &str = "ch3_priority_high";
+const MID: &str = "ch3_priority_mid";
+/// `SchedParam::policy` of round-robin and stride, the policies that go by
+/// priority.
+const POLICY_ROUND_ROBIN: usize = 0;
+const POLICY_STRIDE: usize = 1;
+
+fn spin_delay() {
+    let mut j = true;
+    for _ in 0..10 {
+        j = !j;
+    }
+}
+
+const MAX_TIME: isize = 1000;
+pub fn count_during(prio: isize) -> isize {
+    assert_eq!(set_priority(prio), prio);
+    // Let the other priority tests set their priorities before counting.
+    yield_();
+    let start_time = get_time();
+    let mut acc = 0;
+    loop {
+        spin_delay();
+        acc += 1;
+        if acc % 400 == 0 {
+            let time = get_time() - start_time;
+            if time > MAX_TIME {
+                return acc;
+            }
+        }
+    }
+}
+
+fn cpu_clocks_at_exit(tid: usize) -> usize {
+    loop {
+        let info = TaskInfo::new();
+        assert_eq!(0, sys_task_info_of(tid, &info));
+        if info.status == TaskStatus::Zombie {
+            return info.cpu_clocks;
+        }
+        yield_();
+    }
+}
+
+#[no_mangle]
+pub fn main() -> usize {
+    let prio = 4;
+    let count = count_during(prio);
+    println!("priority = {}, count = {}, finished at {}ms", prio, count, get_time());
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    let low_clocks = info.cpu_clocks;
+
+    let mut param = SchedParam::default();
+    assert_eq!(0, sys_sched_getparam(getpid() as usize, &mut param));
+    if param.policy != POLICY_ROUND_ROBIN && param.policy != POLICY_STRIDE {
+        println!("The scheduler doesn't go by priority, skipped");
+        return 0;
+    }
+    let (high, mid) = match (find_task(HIGH), find_task(MID)) {
+        (Some(high), Some(mid)) => (high, mid),
+        _ => {
+            println!("ch3_priority_high or ch3_priority_mid isn't loaded, skipped");
+            return 0;
+        }
+    };
+    let (high_clocks, mid_clocks) = (cpu_clocks_at_exit(high), cpu_clocks_at_exit(mid));
+    println!("cpu clocks: high {}, mid {}, low {}", high_clocks, mid_clocks, low_clocks);
+    assert!(high_clocks > mid_clocks && high_clocks > low_clocks);
+    // Round-robin runs ch3_priority_high alone for as long as it counts, so
+    // the other two hardly run at all, and either may come out ahead.
+    // Stride shares the CPU in proportion to priority.
+    if param.policy == POLICY_STRIDE {
+        assert!(mid_clocks > low_clocks);
+    }
+    println!("Test priority OK!");
+    0
+}
@@ -0,0 +1,44 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+use user_lib::{get_time, set_priority, yield_};
+
+/*
+理想结果：ch3_priority_high 最先完成且 count 最大，ch3_priority_low 最后完成
+*/
+
+fn spin_delay() {
+    let mut j = true;
+    for _ in 0..10 {
+        j = !j;
+    }
+}
+
+const MAX_TIME: isize = 1000;
+pub fn count_during(prio: isize) -> isize {
+    assert_eq!(set_priority(prio), prio);
+    // Let the other priority tests set their priorities before counting.
+    yield_();
+    let start_time = get_time();
+    let mut acc = 0;
+    loop {
+        spin_delay();
+        acc += 1;
+        if acc % 400 == 0 {
+            let time = get_time() - start_time;
+            if time > MAX_TIME {
+                return acc;
+            }
+        }
+    }
+}
+
+#[no_mangle]
+pub fn main() -> usize {
+    let prio = 16;
+    let count = count_during(prio);
+    println!("priority = {}, count = {}, finished at {}ms", prio, count, get_time());
+    0
+}
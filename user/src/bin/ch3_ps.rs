@@ -0,0 +1,56 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+extern crate alloc;
+
+use alloc::vec;
+use user_lib::{getpid, sys_get_task_count, sys_list_tasks, TaskBrief, TaskStatus};
+
+/// 正确输出：（无报错信息）
+///   ID STATUS     CPU(clocks) PRIO
+///    0 Zombie        ...        16
+/// ...
+/// Test ps OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut briefs = vec![TaskBrief::new(); sys_get_task_count() as usize];
+    let count = sys_list_tasks(&mut briefs);
+    assert!(count > 0);
+    let briefs = &briefs[..count as usize];
+
+    println!("  ID STATUS     CPU(clocks) PRIO");
+    for brief in briefs {
+        println!(
+            "{:>4} {:<10} {:>11} {:>4}",
+            brief.id,
+            // `Debug` of the enum isn't padded.
+            match brief.status {
+                TaskStatus::UnInit => "UnInit",
+                TaskStatus::Ready => "Ready",
+                TaskStatus::Running => "Running",
+                TaskStatus::Exited => "Exited",
+                TaskStatus::Blocked => "Blocked",
+                TaskStatus::Zombie => "Zombie",
+            },
+            brief.cpu_clocks,
+            brief.priority
+        );
+    }
+    // One line per task, in id order, and only this one is running.
+    assert!(briefs.iter().enumerate().all(|(i, brief)| brief.id == i));
+    let pid = getpid() as usize;
+    for brief in briefs {
+        assert_eq!(brief.id == pid, brief.status == TaskStatus::Running);
+    }
+    assert!(briefs[pid].cpu_clocks > 0);
+
+    // Capped at the room given.
+    let mut two = [TaskBrief::new(); 2];
+    assert_eq!(2.min(count), sys_list_tasks(&mut two));
+    assert_eq!(0, sys_list_tasks(&mut []));
+    println!("Test ps OK!");
+    0
+}
@@ -0,0 +1,21 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::sys_reboot;
+
+/// 正确输出：（以 PRIVILEGED_APPS=ch3_reboot 构建内核时重启，启动信息再次出现；否则跳过）
+/// [ INFO][task ...] application `...` rebooted the machine
+
+const WARM: usize = 2;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    assert_eq!(-1, sys_reboot(0));
+    // Doesn't return if the kernel trusts this app.
+    assert_eq!(-1, sys_reboot(WARM));
+    println!("not privileged in this build, skipped");
+    0
+}
@@ -0,0 +1,22 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::sys_reboot;
+
+/// 正确输出：（无报错信息）
+/// Test reboot denied OK!
+
+const COLD: usize = 1;
+const WARM: usize = 2;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Never one of the kernel's `PRIVILEGED_APPS`.
+    assert_eq!(-1, sys_reboot(COLD));
+    assert_eq!(-1, sys_reboot(WARM));
+    println!("Test reboot denied OK!");
+    0
+}
@@ -0,0 +1,39 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    find_task, sys_spawn_app, sys_task_info_of, sys_waittid_exit_code, yield_, TaskInfo, TaskStatus,
+};
+
+/// 正确输出：（无报错信息）
+/// Test reload zeroing OK!
+
+/// Wait for `tid` to exit, reap it and return its exit code.
+fn wait_for(tid: usize) -> i32 {
+    loop {
+        let info = TaskInfo::new();
+        assert_eq!(0, sys_task_info_of(tid, &info));
+        if info.status == TaskStatus::Zombie {
+            break;
+        }
+        yield_();
+    }
+    let mut exit_code = 0;
+    assert_eq!(0, sys_waittid_exit_code(tid, &mut exit_code));
+    exit_code
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let tid = find_task("ch3_reload_worker").expect("worker isn't loaded");
+    // The boot run leaves its sentinels behind, the next one must not see
+    // them.
+    assert_eq!(0, wait_for(tid));
+    assert_eq!(tid as isize, sys_spawn_app(tid));
+    assert_eq!(0, wait_for(tid));
+    println!("Test reload zeroing OK!");
+    0
+}
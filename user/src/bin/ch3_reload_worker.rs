@@ -0,0 +1,41 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::ptr::{read_volatile, write_volatile};
+use user_lib::sys_sbrk;
+
+/// 正确输出：（运行两次，第二次由 ch3_reload_launcher 启动）
+/// reload worker clean
+/// reload worker clean
+
+const SENTINEL: u64 = 0x5e41_71e1_dead_beef;
+
+/// In .bss, which isn't part of the image, so only zeroing the slot clears it.
+static mut BSS_WORD: u64 = 0;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let heap = sys_sbrk(8) as *mut u64;
+    assert!(!heap.is_null());
+    // Well below this frame, still within the user stack.
+    let marker = 0u64;
+    let stack = (&marker as *const u64 as usize - 2048) as *mut u64;
+
+    let words = unsafe { [core::ptr::addr_of_mut!(BSS_WORD), heap, stack] };
+    // All three are zeroed on load, whatever the last run left.
+    for (i, &word) in words.iter().enumerate() {
+        let value = unsafe { read_volatile(word) };
+        if value != 0 {
+            println!("reload worker: word {} is {:#x}, not zeroed", i, value);
+            return 1;
+        }
+    }
+    for &word in words.iter() {
+        unsafe { write_volatile(word, SENTINEL) };
+    }
+    println!("reload worker clean");
+    0
+}
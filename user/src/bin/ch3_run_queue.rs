@@ -0,0 +1,38 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{getpid, sys_get_task_count, sys_task_info_of, yield_, TaskInfo, TaskStatus};
+
+/// 正确输出：（无报错信息）
+/// Test run queue OK!
+///
+/// Most useful with the run queue as full as it gets, i.e. with as many
+/// apps loaded as the kernel's `MAX_TASK_NUM` allows.
+
+const ROUNDS: usize = 50;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let pid = getpid() as usize;
+    for _ in 0..ROUNDS {
+        yield_();
+        // Whatever the queue did, the only running task is the one asking.
+        let mut running = 0;
+        for tid in 0..sys_get_task_count() as usize {
+            let info = TaskInfo::new();
+            if sys_task_info_of(tid, &info) != 0 {
+                continue;
+            }
+            if info.status == TaskStatus::Running {
+                assert_eq!(pid, tid);
+                running += 1;
+            }
+        }
+        assert_eq!(1, running);
+    }
+    println!("Test run queue OK!");
+    0
+}
@@ -0,0 +1,41 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::sys_sbrk;
+
+/// 正确输出：（无报错信息）
+/// Test sbrk OK!
+
+const PAGE_SIZE: usize = 4096;
+/// The kernel's `USER_HEAP_SIZE`.
+const USER_HEAP_SIZE: isize = 0x4000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let bottom = sys_sbrk(0);
+    assert!(bottom > 0);
+    // Can't shrink below the bottom or grow past the region.
+    assert_eq!(-1, sys_sbrk(-1));
+    assert_eq!(-1, sys_sbrk(USER_HEAP_SIZE + 1));
+    assert_eq!(-1, sys_sbrk(isize::MIN));
+    assert_eq!(bottom, sys_sbrk(0));
+
+    assert_eq!(bottom, sys_sbrk(PAGE_SIZE as isize));
+    let page = unsafe { core::slice::from_raw_parts_mut(bottom as *mut u8, PAGE_SIZE) };
+    for (i, byte) in page.iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    assert!(page.iter().enumerate().all(|(i, &byte)| byte == i as u8));
+    assert_eq!(bottom + PAGE_SIZE as isize, sys_sbrk(0));
+
+    // The rest of the region, then all of it back.
+    assert_eq!(bottom + PAGE_SIZE as isize, sys_sbrk(USER_HEAP_SIZE - PAGE_SIZE as isize));
+    assert_eq!(-1, sys_sbrk(1));
+    assert_eq!(bottom + USER_HEAP_SIZE, sys_sbrk(-USER_HEAP_SIZE));
+    assert_eq!(bottom, sys_sbrk(0));
+    println!("Test sbrk OK!");
+    0
+}
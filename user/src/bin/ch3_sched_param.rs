@@ -0,0 +1,54 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    getpid, sys_get_task_count, sys_get_ticks, sys_sched_getparam, sys_sched_setparam, SchedParam,
+};
+
+/// 正确输出：（无报错信息）
+/// Test sched param OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let tid = getpid() as usize;
+    let mut param = SchedParam::default();
+    assert_eq!(0, sys_sched_getparam(tid, &mut param));
+    assert!(param.priority >= 2 && param.tickets > 0);
+
+    let wanted = SchedParam {
+        policy: param.policy,
+        priority: param.priority + 4,
+        tickets: param.tickets * 2,
+        deadline: sys_get_ticks() as usize + 1_000_000_000,
+    };
+    assert_eq!(0, sys_sched_setparam(tid, &wanted));
+    let mut got = SchedParam::default();
+    assert_eq!(0, sys_sched_getparam(tid, &mut got));
+    assert_eq!(wanted, got);
+
+    // Each field is validated, and nothing is applied if one is off.
+    let bad = [
+        SchedParam { policy: param.policy + 1, ..wanted },
+        SchedParam { priority: 1, ..wanted },
+        SchedParam { tickets: 0, ..wanted },
+        SchedParam { tickets: usize::MAX, ..wanted },
+    ];
+    for bad in bad.iter() {
+        assert_eq!(-1, sys_sched_setparam(tid, bad));
+    }
+    let no_such_task = sys_get_task_count() as usize;
+    assert_eq!(-1, sys_sched_setparam(no_such_task, &wanted));
+    assert_eq!(-1, sys_sched_getparam(no_such_task, &mut got));
+    assert_eq!(0, sys_sched_getparam(tid, &mut got));
+    assert_eq!(wanted, got);
+
+    // Back to how it was, without a deadline.
+    assert_eq!(0, sys_sched_setparam(tid, &param));
+    assert_eq!(0, sys_sched_getparam(tid, &mut got));
+    assert_eq!(param, got);
+    println!("Test sched param OK!");
+    0
+}
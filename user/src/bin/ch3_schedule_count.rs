@@ -0,0 +1,30 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{task_info, yield_, TaskInfo};
+
+/// 正确输出：（无报错信息）
+/// Test schedule count OK!
+
+const YIELD_TIMES: usize = 100;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let before = TaskInfo::new();
+    assert_eq!(0, task_info(&before));
+    for _ in 0..YIELD_TIMES {
+        yield_();
+    }
+    let after = TaskInfo::new();
+    assert_eq!(0, task_info(&after));
+    // Every yield is followed by at least one reschedule; timer preemption
+    // may add more.
+    let scheduled = after.schedule_count - before.schedule_count;
+    assert!(scheduled >= YIELD_TIMES);
+    println!("scheduled {} times for {} yields", scheduled, YIELD_TIMES);
+    println!("Test schedule count OK!");
+    0
+}
@@ -0,0 +1,27 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, sleep_blocking, task_info, TaskInfo, SYSCALL_SLEEP, SYSCALL_YIELD};
+
+/// 正确输出：（无报错信息）
+/// Test sleep blocking OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let start = get_time();
+    sleep_blocking(100);
+    let end = get_time();
+    assert!(end - start >= 100);
+
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    // The task slept in the kernel rather than polling with yield.
+    assert_eq!(1, info.syscall_times[SYSCALL_SLEEP]);
+    assert_eq!(0, info.syscall_times[SYSCALL_YIELD]);
+    println!("slept {}ms", end - start);
+    println!("Test sleep blocking OK!");
+    0
+}
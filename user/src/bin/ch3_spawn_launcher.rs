@@ -0,0 +1,62 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    find_task, getpid, sys_get_task_count, sys_spawn_app, sys_task_info_of, sys_waittid_exit_code,
+    yield_, TaskInfo, TaskStatus, SYSCALL_YIELD,
+};
+
+/// 正确输出：（无报错信息）
+/// Test spawn OK!
+
+/// Worker apps and their exit codes.
+const WORKERS: [(&str, i32); 2] = [("ch3_spawn_worker_a", 61), ("ch3_spawn_worker_b", 62)];
+
+/// Wait for `tid` to exit and reap it.
+fn wait_for(tid: usize) -> (TaskInfo, i32) {
+    let info = loop {
+        let info = TaskInfo::new();
+        assert_eq!(0, sys_task_info_of(tid, &info));
+        if info.status == TaskStatus::Zombie {
+            break info;
+        }
+        yield_();
+    };
+    // Exited but not reaped, so its slot, the only one it can run in, is
+    // still taken.
+    assert_eq!(-1, sys_spawn_app(tid));
+    let mut exit_code = 0;
+    assert_eq!(0, sys_waittid_exit_code(tid, &mut exit_code));
+    (info, exit_code)
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    // Running tasks and apps that don't exist can't be spawned.
+    assert_eq!(-1, sys_spawn_app(getpid() as usize));
+    assert_eq!(-1, sys_spawn_app(sys_get_task_count() as usize));
+
+    let mut tids = [0; WORKERS.len()];
+    for (tid, (name, exit_code)) in tids.iter_mut().zip(WORKERS) {
+        *tid = find_task(name).expect("worker isn't loaded");
+        // Every app runs once at boot, the slot is free after that.
+        assert_eq!(exit_code, wait_for(*tid).1);
+    }
+
+    for &tid in tids.iter() {
+        assert_eq!(tid as isize, sys_spawn_app(tid));
+        // Spawned, so in use again.
+        assert_eq!(-1, sys_spawn_app(tid));
+    }
+    for (&tid, (_, exit_code)) in tids.iter().zip(WORKERS) {
+        let (info, code) = wait_for(tid);
+        assert_eq!(exit_code, code);
+        // Stats start over with the new run.
+        assert_eq!(3, info.syscall_times[SYSCALL_YIELD]);
+    }
+    println!("Test spawn OK!");
+    0
+}
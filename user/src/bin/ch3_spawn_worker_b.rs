@@ -0,0 +1,20 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::yield_;
+
+/// 正确输出：（运行两次，第二次由 ch3_spawn_launcher 启动）
+/// spawn worker b
+/// spawn worker b
+
+#[no_mangle]
+pub fn main() -> i32 {
+    for _ in 0..3 {
+        yield_();
+    }
+    println!("spawn worker b");
+    62
+}
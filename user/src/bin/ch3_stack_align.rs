@@ -0,0 +1,44 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::arch::asm;
+
+/// 正确输出：（无报错信息）
+/// Test stack align OK!
+///
+/// The ABI keeps `sp` 16-byte aligned in every function, provided the
+/// kernel starts the app with an aligned one.
+
+fn sp() -> usize {
+    let sp: usize;
+    unsafe { asm!("mv {}, sp", out(reg) sp) };
+    sp
+}
+
+#[inline(never)]
+fn nested(depth: usize) -> usize {
+    assert_eq!(0, sp() % 16, "sp {:#x} at depth {}", sp(), depth);
+    if depth == 0 { 0 } else { nested(depth - 1) + 1 }
+}
+
+/// Doubles are spilled to the stack at `sp` offsets that assume alignment.
+#[inline(never)]
+fn spill_doubles(x: f64) -> f64 {
+    let values = [x, x * 2.0, x * 3.0, x * 4.0];
+    // Make sure they are really in memory.
+    let values = unsafe { core::ptr::read_volatile(&values) };
+    assert_eq!(0, values.as_ptr() as usize % 8);
+    values.iter().sum()
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    assert_eq!(0, sp() % 16);
+    assert_eq!(8, nested(8));
+    assert_eq!(15.0, spill_doubles(1.5));
+    println!("Test stack align OK!");
+    0
+}
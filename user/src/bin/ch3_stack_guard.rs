@@ -0,0 +1,22 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::sys_clobber_stack_guard;
+
+/// 正确输出：（内核启用 fault-injection 时打印以下信息后关机，否则跳过）
+/// panic in file `src/task.rs` at line ...: kernel stack of task `...` overflowed
+/// ...
+
+#[no_mangle]
+pub fn main() -> i32 {
+    if sys_clobber_stack_guard() != 0 {
+        println!("sys_clobber_stack_guard is disabled, skipped");
+        return 0;
+    }
+    // Doesn't return: printing is the next syscall, which finds the guard gone.
+    println!("FAIL: the kernel didn't notice its stack overflowed");
+    0
+}
@@ -0,0 +1,48 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use core::ptr::{read_volatile, write_volatile};
+use user_lib::sys_stack_usage;
+
+/// 正确输出：（无报错信息）
+/// stack usage: ... bytes, ... bytes at depth ..., ... bytes at depth ...
+/// Test stack usage OK!
+
+/// Bytes of locals each level of `recurse` keeps on the stack.
+const FRAME_BYTES: usize = 256;
+const SHALLOW: usize = 4;
+const DEEP: usize = 16;
+
+#[inline(never)]
+fn recurse(depth: usize) -> u8 {
+    let mut frame = [0u8; FRAME_BYTES];
+    for (i, byte) in frame.iter_mut().enumerate() {
+        // Volatile, so the frame is really written and not optimized away.
+        unsafe { write_volatile(byte, (i + depth) as u8) };
+    }
+    let below = if depth > 1 { recurse(depth - 1) } else { 0 };
+    below.wrapping_add(unsafe { read_volatile(&frame[depth % FRAME_BYTES]) })
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let start = sys_stack_usage() as usize;
+    recurse(SHALLOW);
+    let shallow = sys_stack_usage() as usize;
+    recurse(DEEP);
+    let deep = sys_stack_usage() as usize;
+    println!(
+        "stack usage: {} bytes, {} bytes at depth {}, {} bytes at depth {}",
+        start, shallow, SHALLOW, deep, DEEP
+    );
+    assert!(start > 0);
+    assert!(shallow >= SHALLOW * FRAME_BYTES);
+    // Both recursions start from the same frame of `main`, and nothing
+    // before them went nearly as deep.
+    assert!(deep >= shallow + (DEEP - SHALLOW) * FRAME_BYTES);
+    println!("Test stack usage OK!");
+    0
+}
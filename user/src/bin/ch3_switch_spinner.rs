@@ -0,0 +1,29 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, sys_trap_stats, task_info, TaskInfo, TrapStats};
+
+/// 正确输出：（无报错信息）
+/// Test involuntary switches OK!
+
+const SPIN_MS: isize = 200;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let start = get_time();
+    while get_time() < start + SPIN_MS {}
+
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    let stats = TrapStats::new();
+    assert_eq!(0, sys_trap_stats(&stats));
+    // Never yields, so only the timer ever took the CPU away, if the
+    // kernel preempts at all.
+    assert_eq!(0, info.voluntary_switches);
+    assert!(info.involuntary_switches > 0 || stats.task_timer_interrupts == 0);
+    println!("Test involuntary switches OK!");
+    0
+}
@@ -0,0 +1,26 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{task_info, yield_, TaskInfo};
+
+/// 正确输出：（无报错信息）
+/// Test voluntary switches OK!
+
+const YIELDS: usize = 20;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    for _ in 0..YIELDS {
+        yield_();
+    }
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    assert!(info.voluntary_switches >= YIELDS);
+    // Giving the CPU up this often leaves the timer little to preempt.
+    assert!(info.voluntary_switches > info.involuntary_switches);
+    println!("Test voluntary switches OK!");
+    0
+}
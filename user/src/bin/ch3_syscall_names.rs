@@ -0,0 +1,31 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::sys_syscall_name;
+
+/// 正确输出：（无报错信息）
+/// Test syscall names OK!
+
+fn syscall_name(id: usize, buf: &mut [u8]) -> Option<&str> {
+    let len = sys_syscall_name(id, buf);
+    (len >= 0).then(move || core::str::from_utf8(&buf[..len as usize]).unwrap())
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut buf = [0u8; 32];
+    assert_eq!(Some("write"), syscall_name(64, &mut buf));
+    assert_eq!(Some("exit"), syscall_name(93, &mut buf));
+    assert_eq!(Some("yield"), syscall_name(124, &mut buf));
+    assert_eq!(Some("get_time"), syscall_name(169, &mut buf));
+    assert_eq!(Some("task_info"), syscall_name(410, &mut buf));
+    assert_eq!(None, syscall_name(499, &mut buf));
+    assert_eq!(None, syscall_name(usize::MAX, &mut buf));
+    // Too small for "task_info".
+    assert_eq!(-1, sys_syscall_name(410, &mut buf[..4]));
+    println!("Test syscall names OK!");
+    0
+}
@@ -0,0 +1,29 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, task_info, TaskInfo, SYSCALL_GETTIMEOFDAY};
+
+/// 正确输出：（无报错信息）
+/// Test syscall stress OK!
+
+// Long enough to be preempted by many timer interrupts in the middle of syscalls.
+const MAX_TIME: isize = 1000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let start = get_time();
+    let mut calls = 1;
+    while get_time() - start < MAX_TIME {
+        calls += 1;
+    }
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    // Every syscall must be counted exactly once, even if interrupted.
+    assert_eq!(calls + 1, info.syscall_times[SYSCALL_GETTIMEOFDAY]);
+    println!("{} syscalls in {}ms", calls + 1, MAX_TIME);
+    println!("Test syscall stress OK!");
+    0
+}
@@ -0,0 +1,29 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{getpid, sys_get_task_count, sys_task_name, task_info, TaskInfo, SYSCALL_GET_TASK_COUNT};
+
+/// 正确输出：（无报错信息）
+/// ... tasks
+/// Test task count OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let count = sys_get_task_count();
+    println!("{} tasks", count);
+    assert!(count > getpid());
+
+    // Exactly the ids below the count name a linked app.
+    let mut buf = [0u8; 32];
+    assert!(sys_task_name(count as usize - 1, &mut buf) > 0);
+    assert_eq!(-1, sys_task_name(count as usize, &mut buf));
+
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    assert_eq!(1, info.syscall_times[SYSCALL_GET_TASK_COUNT]);
+    println!("Test task count OK!");
+    0
+}
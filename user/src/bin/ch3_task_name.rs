@@ -0,0 +1,36 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{getpid, sys_get_task_count, sys_task_name};
+
+/// 正确输出：（无报错信息）
+/// Test task name OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let task_count = sys_get_task_count() as usize;
+    let mut name = [0u8; 64];
+    let len = sys_task_name(getpid() as usize, &mut name);
+    assert_eq!(b"ch3_task_name", &name[..len as usize]);
+
+    // Too small a buffer, or no such task.
+    assert_eq!(-1, sys_task_name(getpid() as usize, &mut name[..4]));
+    assert_eq!(-1, sys_task_name(task_count, &mut name));
+
+    // Apps are loaded in order of their names.
+    let mut prev = [0u8; 64];
+    let mut prev_len = 0;
+    for tid in 0..task_count {
+        let len = sys_task_name(tid, &mut name);
+        assert!(len > 0);
+        let len = len as usize;
+        assert!(prev[..prev_len] < name[..len]);
+        prev[..len].copy_from_slice(&name[..len]);
+        prev_len = len;
+    }
+    println!("Test task name OK!");
+    0
+}
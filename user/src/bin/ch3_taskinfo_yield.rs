@@ -0,0 +1,33 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    syscall, task_info, yield_, TaskInfo, TaskStatus, SYSCALL_TASK_INFO, SYSCALL_YIELD,
+};
+
+/// 正确输出：（无报错信息）
+/// yield = 3, task_info = 2
+/// Test task info yield OK!
+
+#[no_mangle]
+pub fn main() -> i32 {
+    for _ in 0..3 {
+        yield_();
+    }
+    // A null TaskInfo pointer must be rejected, but still counts as a call.
+    assert_eq!(-1, syscall(SYSCALL_TASK_INFO, [0, 0, 0]));
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    assert_eq!(3, info.syscall_times[SYSCALL_YIELD]);
+    assert_eq!(2, info.syscall_times[SYSCALL_TASK_INFO]);
+    assert!(info.status == TaskStatus::Running);
+    println!(
+        "yield = {}, task_info = {}",
+        info.syscall_times[SYSCALL_YIELD], info.syscall_times[SYSCALL_TASK_INFO]
+    );
+    println!("Test task info yield OK!");
+    0
+}
@@ -0,0 +1,46 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sleep_blocking, sys_get_ticks, sys_idle_time, sys_trap_stats, TrapStats};
+
+/// 正确输出：（无报错信息）
+/// timer interrupts while idle: ...
+/// Test tickless OK!
+
+const NAP_MS: usize = 500;
+/// The CPU is only idle once every other task sleeps or has exited too,
+/// so keep napping until a nap is spent almost entirely idle.
+const MAX_NAPS: usize = 20;
+/// Interrupts allowed in an idle nap: our own wake-up, plus a few for the
+/// bit of it other tasks ran. A fixed 100 Hz tick would take 50.
+const MAX_INTERRUPTS: usize = 5;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    for _ in 0..MAX_NAPS {
+        let before = TrapStats::new();
+        assert_eq!(0, sys_trap_stats(&before));
+        let idle_before = sys_idle_time();
+        let start = sys_get_ticks();
+        sleep_blocking(NAP_MS);
+        let elapsed = sys_get_ticks() - start;
+        let idle = sys_idle_time() - idle_before;
+        let after = TrapStats::new();
+        assert_eq!(0, sys_trap_stats(&after));
+
+        if idle * 100 < elapsed * 98 {
+            continue;
+        }
+        let interrupts = after.timer_interrupts - before.timer_interrupts;
+        println!("timer interrupts while idle: {}", interrupts);
+        assert!(interrupts <= MAX_INTERRUPTS);
+        println!("Test tickless OK!");
+        return 0;
+    }
+    println!("the CPU was never idle for a whole nap, skipped");
+    println!("Test tickless OK!");
+    0
+}
@@ -0,0 +1,38 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    sleep_blocking, sys_get_clock_freq, sys_get_ticks, task_info, TaskInfo,
+    SYSCALL_GET_CLOCK_FREQ, SYSCALL_GET_TICKS,
+};
+
+/// 正确输出：（无报错信息）
+/// Test ticks OK!
+
+const DELAY_MS: isize = 20;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let freq = sys_get_clock_freq();
+    assert!(freq > 0);
+
+    let first = sys_get_ticks();
+    let second = sys_get_ticks();
+    assert!(second > first);
+
+    // Ticks convert to time with the frequency.
+    let before = sys_get_ticks();
+    sleep_blocking(DELAY_MS as usize);
+    let elapsed_ms = (sys_get_ticks() - before) * 1000 / freq;
+    assert!(elapsed_ms >= DELAY_MS, "slept only {} ms", elapsed_ms);
+
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    assert_eq!(1, info.syscall_times[SYSCALL_GET_CLOCK_FREQ]);
+    assert_eq!(4, info.syscall_times[SYSCALL_GET_TICKS]);
+    println!("Test ticks OK!");
+    0
+}
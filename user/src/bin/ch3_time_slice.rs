@@ -0,0 +1,47 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{task_info, TaskInfo};
+
+/// 正确输出：（无报错信息）
+/// about ... reschedules per second of cpu time
+/// Test time slice OK!
+///
+/// Under round-robin the reported rate follows the kernel's `TICKS_PER_SEC`,
+/// compare e.g. `make run` with `make run TICKS_PER_SEC=1000`.
+
+/// The kernel's `CLOCK_FREQ`.
+const CLOCK_FREQ: usize = 12500000;
+/// CPU time to spin for, in clocks.
+const SPIN_CLOCKS: usize = CLOCK_FREQ / 2;
+
+fn cpu_clocks(info: &TaskInfo) -> usize {
+    info.kernel_clocks + info.user_clocks
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let before = TaskInfo::new();
+    assert_eq!(0, task_info(&before));
+    let after = TaskInfo::new();
+    loop {
+        assert_eq!(0, task_info(&after));
+        if cpu_clocks(&after) - cpu_clocks(&before) >= SPIN_CLOCKS {
+            break;
+        }
+    }
+    // Without yielding, every reschedule comes from a timer tick landing
+    // while this task runs.
+    let scheduled = after.schedule_count - before.schedule_count;
+    let clocks = cpu_clocks(&after) - cpu_clocks(&before);
+    assert!(scheduled > 0);
+    println!(
+        "about {} reschedules per second of cpu time",
+        scheduled * CLOCK_FREQ / clocks
+    );
+    println!("Test time slice OK!");
+    0
+}
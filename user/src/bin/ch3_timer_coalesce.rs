@@ -0,0 +1,48 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sys_trap_stats, yield_, TrapStats};
+
+/// 正确输出：（无报错信息）
+/// set_timer: ... calls, ... skipped for ... yields
+/// Test timer coalescing OK!
+
+const YIELDS: usize = 200;
+/// Other tasks running long between two yields leave nothing to coalesce,
+/// so keep trying until a round of yields is quick.
+const MAX_ROUNDS: usize = 20;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    for _ in 0..MAX_ROUNDS {
+        let before = TrapStats::new();
+        assert_eq!(0, sys_trap_stats(&before));
+        for _ in 0..YIELDS {
+            yield_();
+        }
+        let after = TrapStats::new();
+        assert_eq!(0, sys_trap_stats(&after));
+
+        let calls = after.set_timer_calls - before.set_timer_calls;
+        let skipped = after.set_timer_skipped - before.set_timer_skipped;
+        // Without preemption the timer is never set at all.
+        if calls == 0 && skipped == 0 {
+            println!("the timer is off, skipped");
+            println!("Test timer coalescing OK!");
+            return 0;
+        }
+        if calls * 2 > YIELDS {
+            continue;
+        }
+        println!("set_timer: {} calls, {} skipped for {} yields", calls, skipped, YIELDS);
+        assert!(skipped > 0);
+        println!("Test timer coalescing OK!");
+        return 0;
+    }
+    println!("other tasks kept the yields apart, skipped");
+    println!("Test timer coalescing OK!");
+    0
+}
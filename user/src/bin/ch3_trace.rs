@@ -0,0 +1,51 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    getpid, sys_dump_trace, sys_syscall_name, yield_, TraceEntry, SYSCALL_DUMP_TRACE,
+    SYSCALL_SYSCALL_NAME,
+};
+
+/// 正确输出：（无报错信息）
+/// Test trace OK!
+
+/// Told apart by their first argument, the syscall whose name is asked.
+const PROBES: [usize; 3] = [64, 93, 124];
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let pid = getpid() as usize;
+    let mut name = [0u8; 32];
+    // Start on a fresh time slice, so that other tasks are unlikely to fill
+    // the trace between the probes and the dump.
+    yield_();
+    for &id in PROBES.iter() {
+        assert!(sys_syscall_name(id, &mut name) > 0);
+    }
+    let mut entries = [TraceEntry::default(); 32];
+    let count = sys_dump_trace(&mut entries);
+    assert!(count > 0 && count as usize <= entries.len());
+
+    // The last four of this task's: the probes, then the dump.
+    let mut last = [TraceEntry::default(); 4];
+    let mut found = 0;
+    for entry in entries[..count as usize].iter().filter(|entry| entry.task_id == pid) {
+        last.rotate_left(1);
+        last[3] = *entry;
+        found += 1;
+    }
+    assert!(found >= 4);
+    for (entry, &id) in last.iter().zip(PROBES.iter()) {
+        assert_eq!(entry.syscall_id, SYSCALL_SYSCALL_NAME);
+        assert_eq!(entry.arg0, id);
+    }
+    // The dump is recorded before it is copied.
+    assert_eq!(last[3].syscall_id, SYSCALL_DUMP_TRACE);
+    assert_eq!(last[3].arg0, entries.as_ptr() as usize);
+    assert!(last.windows(2).all(|pair| pair[0].time <= pair[1].time));
+    println!("Test trace OK!");
+    0
+}
@@ -0,0 +1,37 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{get_time, sys_trap_stats, TrapStats};
+
+/// 正确输出：（无报错信息）
+/// traps = ..., timer interrupts = ..., syscalls = ...
+/// Test trap stats OK!
+
+const SPIN_MS: isize = 500;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let before = TrapStats::new();
+    assert_eq!(0, sys_trap_stats(&before));
+
+    // Spin long enough for the timer to fire, unless the kernel never preempts.
+    let start = get_time();
+    while get_time() < start + SPIN_MS {}
+
+    let after = TrapStats::new();
+    assert_eq!(0, sys_trap_stats(&after));
+    println!(
+        "traps = {}, timer interrupts = {}, syscalls = {}, ours = {}",
+        after.traps, after.timer_interrupts, after.syscalls, after.task_timer_interrupts
+    );
+    // Every get_time above was a syscall, so trapped.
+    assert!(after.syscalls > before.syscalls);
+    assert!(after.traps >= after.syscalls + after.timer_interrupts);
+    assert!(after.timer_interrupts >= after.task_timer_interrupts);
+    assert!(after.task_timer_interrupts >= before.task_timer_interrupts);
+    println!("Test trap stats OK!");
+    0
+}
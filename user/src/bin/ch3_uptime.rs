@@ -0,0 +1,27 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{sleep_blocking, sys_uptime};
+
+/// 正确输出：（无报错信息）
+/// Test uptime OK!
+
+const SLEEP_MS: isize = 100;
+/// Other tasks may run for a while before this one is picked again.
+const TOLERANCE_MS: isize = 200;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let before = sys_uptime();
+    assert!(before >= 0);
+    sleep_blocking(SLEEP_MS as usize);
+    let after = sys_uptime();
+    let delta = after - before;
+    assert!(delta >= SLEEP_MS - 1, "slept {}ms by uptime", delta);
+    assert!(delta <= SLEEP_MS + TOLERANCE_MS, "slept {}ms by uptime", delta);
+    println!("Test uptime OK!");
+    0
+}
@@ -0,0 +1,27 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{write, STDIN, STDOUT};
+
+/// 正确输出：（无报错信息）
+/// to stderr
+/// Test write fd OK!
+
+const STDERR: usize = 2;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let msg = b"to stderr\n";
+    assert_eq!(-1, write(STDIN, msg));
+    assert_eq!(-1, write(3, msg));
+    assert_eq!(-1, write(usize::MAX, msg));
+    assert_eq!(msg.len() as isize, write(STDERR, msg));
+    // Not UTF-8, written as is.
+    assert_eq!(1, write(STDOUT, &[0xff]));
+    println!("");
+    println!("Test write fd OK!");
+    0
+}
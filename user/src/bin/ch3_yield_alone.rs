@@ -0,0 +1,53 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    get_time, getpid, sys_get_task_count, sys_task_info_of, task_info, yield_, TaskInfo, TaskStatus,
+};
+
+/// 正确输出：（无报错信息）
+/// Test yield alone OK!
+///
+/// Yields once every other task has stopped running, so that the kernel
+/// can only pick this task again.
+
+/// How long to wait for the other tasks, some of which may never finish
+/// without console input.
+const WAIT_MS: isize = 10000;
+const ROUNDS: usize = 100;
+
+fn others_runnable(pid: usize) -> bool {
+    (0..sys_get_task_count() as usize).filter(|&tid| tid != pid).any(|tid| {
+        let info = TaskInfo::new();
+        sys_task_info_of(tid, &info) == 0
+            && matches!(info.status, TaskStatus::Ready | TaskStatus::Running)
+    })
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let pid = getpid() as usize;
+    let start = get_time();
+    while others_runnable(pid) && get_time() - start < WAIT_MS {
+        yield_();
+    }
+    let alone = !others_runnable(pid);
+
+    for _ in 0..ROUNDS {
+        let before = TaskInfo::new();
+        assert_eq!(0, task_info(&before));
+        assert_eq!(0, yield_());
+        let after = TaskInfo::new();
+        assert_eq!(0, task_info(&after));
+        // Rescheduled, timer ticks may add more.
+        assert!(after.schedule_count > before.schedule_count);
+    }
+    if !alone {
+        println!("other tasks kept running, yielded among them instead");
+    }
+    println!("Test yield alone OK!");
+    0
+}
@@ -0,0 +1,24 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{task_info, yield_, TaskInfo, SYSCALL_YIELD};
+
+/// 正确输出：（无报错信息）
+/// Test yield hammer OK!
+
+const YIELD_TIMES: u32 = 100000;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    for _ in 0..YIELD_TIMES {
+        assert_eq!(0, yield_());
+    }
+    let info = TaskInfo::new();
+    assert_eq!(0, task_info(&info));
+    assert_eq!(YIELD_TIMES, info.syscall_times[SYSCALL_YIELD]);
+    println!("Test yield hammer OK!");
+    0
+}
@@ -0,0 +1,32 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{task_info, yield_, TaskInfo, SYSCALL_YIELD};
+
+/// 正确输出：（无报错信息，与 ch3_yield_pong 交替输出）
+/// ping 0
+/// ...
+/// Test yield ping OK!
+
+const ROUNDS: u32 = 10;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    for i in 0..ROUNDS {
+        println!("ping {}", i);
+        let before = TaskInfo::new();
+        assert_eq!(0, task_info(&before));
+        assert_eq!(0, yield_());
+        let after = TaskInfo::new();
+        assert_eq!(0, task_info(&after));
+        // Each yield ran exactly once and gave up the CPU.
+        assert_eq!(i + 1, after.syscall_times[SYSCALL_YIELD]);
+        assert!(after.schedule_count > before.schedule_count);
+        assert_eq!(before.status, after.status);
+    }
+    println!("Test yield ping OK!");
+    0
+}
@@ -0,0 +1,40 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{find_task, sys_task_info_of, sys_yield_to, TaskInfo, TaskStatus};
+
+/// 正确输出：（无报错信息，与 ch3_yield_to_pong 交替输出）
+/// yield to pong 0
+/// ...
+/// Test yield to ping OK!
+
+const PARTNER: &str = "ch3_yield_to_pong";
+const ROUNDS: usize = 10;
+
+fn task_info_of(tid: usize) -> TaskInfo {
+    let info = TaskInfo::new();
+    assert_eq!(0, sys_task_info_of(tid, &info));
+    info
+}
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let partner = find_task(PARTNER).expect("ch3_yield_to_pong isn't loaded");
+    for i in 0..ROUNDS {
+        println!("yield to pong {}", i);
+        let before = task_info_of(partner);
+        if before.status == TaskStatus::Zombie {
+            // Done with its rounds, nobody to yield to.
+            assert_eq!(-1, sys_yield_to(partner));
+            continue;
+        }
+        assert_eq!(TaskStatus::Ready, before.status);
+        assert_eq!(0, sys_yield_to(partner));
+        assert!(task_info_of(partner).schedule_count > before.schedule_count);
+    }
+    println!("Test yield to ping OK!");
+    0
+}
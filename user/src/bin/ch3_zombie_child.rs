@@ -0,0 +1,24 @@
+#![no_std]
+#![no_main]
+
+extern crate user_lib;
+
+use user_lib::{getpid, yield_};
+
+/// 正确输出：（无报错信息）
+/// 由 ch3_zombie_reaper 检查统计信息并回收
+
+/// `getpid` calls that mark this task for ch3_zombie_reaper.
+const MARK_GETPIDS: usize = 7;
+const YIELDS: usize = 3;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    for _ in 0..MARK_GETPIDS {
+        getpid();
+    }
+    for _ in 0..YIELDS {
+        yield_();
+    }
+    43
+}
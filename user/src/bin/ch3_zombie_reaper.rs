@@ -0,0 +1,49 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{
+    sys_get_task_count, sys_task_info_of, sys_waittid_exit_code, yield_, TaskInfo, TaskStatus,
+    SYSCALL_GETPID, SYSCALL_YIELD,
+};
+
+/// 正确输出：（无报错信息）
+/// Test zombie OK!
+
+/// Match ch3_zombie_child.
+const MARK_GETPIDS: u32 = 7;
+const YIELDS: u32 = 3;
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let tid = 'found: loop {
+        for tid in 0..sys_get_task_count() as usize {
+            let info = TaskInfo::new();
+            if sys_task_info_of(tid, &info) == 0
+                && info.status == TaskStatus::Zombie
+                && info.syscall_times[SYSCALL_GETPID] == MARK_GETPIDS
+            {
+                break 'found tid;
+            }
+        }
+        yield_();
+    };
+
+    // The stats of the exited task are still there until it is reaped.
+    let info = TaskInfo::new();
+    assert_eq!(0, sys_task_info_of(tid, &info));
+    assert_eq!(YIELDS, info.syscall_times[SYSCALL_YIELD]);
+    assert_eq!(43, info.exit_code);
+    assert!(info.schedule_count > 0);
+
+    let mut exit_code = 0;
+    assert_eq!(0, sys_waittid_exit_code(tid, &mut exit_code));
+    assert_eq!(43, exit_code);
+    // Reaped tasks are gone.
+    assert_eq!(-1, sys_task_info_of(tid, &info));
+    assert_eq!(-1, sys_waittid_exit_code(tid, &mut exit_code));
+    println!("Test zombie OK!");
+    0
+}
@@ -0,0 +1,18 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+/// Not a ch3 test, since it stops the kernel. Run it alone with
+/// `make run APP=corrupt_load FEATURES=fault-injection` in os3, which flips
+/// a bit of its image once it is copied.
+///
+/// 正确输出：
+/// panic in file `src/task.rs` at line ...: task `corrupt_load` is corrupted after loading, CRC-32 0x... instead of 0x...
+
+#[no_mangle]
+pub fn main() -> i32 {
+    println!("corrupt_load ran: the kernel doesn't have fault-injection");
+    0
+}
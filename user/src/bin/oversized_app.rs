@@ -0,0 +1,21 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+/// Not a ch3 test, since it stops the kernel. Run it alone with
+/// `make run APP=oversized_app` in os3.
+///
+/// 正确输出：
+/// panic: task `oversized_app` is 0x2..... bytes, larger than MAX_IMAGE_SIZE (0x1c000)
+
+/// Initialized, so that it takes up space in the binary rather than in .bss.
+static BLOB: [u8; 0x21000] = [0xa5; 0x21000];
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let last = unsafe { (&BLOB[BLOB.len() - 1] as *const u8).read_volatile() };
+    println!("FAIL: T.T, oversized app was loaded ({:#x})", last);
+    0
+}
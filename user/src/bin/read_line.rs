@@ -0,0 +1,27 @@
+#![no_std]
+#![no_main]
+
+#[macro_use]
+extern crate user_lib;
+
+use user_lib::{flush, read, STDIN};
+
+/// Not a ch3 test, since it waits for console input. Run it alone with
+/// `make run APP=read_line` in os3.
+///
+/// 交互测试：需要在控制台输入一行文字（支持退格）
+/// 正确输出：
+/// >> hello
+/// line = "hello"
+
+#[no_mangle]
+pub fn main() -> i32 {
+    let mut buf = [0u8; 64];
+    print!(">> ");
+    flush();
+    let len = read(STDIN, &mut buf);
+    assert!(len > 0);
+    let line = core::str::from_utf8(&buf[..len as usize]).unwrap();
+    println!("line = {:?}", line.trim_end());
+    0
+}
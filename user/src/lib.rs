@@ -98,12 +98,27 @@ impl TimeVal {
     }
 }
 
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct TimeSpec {
+    pub sec: usize,
+    pub nsec: usize,
+}
+
+impl TimeSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum TaskStatus {
     UnInit,
     Ready,
     Running,
     Exited,
+    Blocked,
+    Zombie,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -114,11 +129,24 @@ pub struct SyscallInfo {
 
 const MAX_SYSCALL_NUM: usize = 500;
 
+#[repr(C)]
 #[derive(Debug)]
 pub struct TaskInfo {
     pub status: TaskStatus,
     pub syscall_times: [u32; MAX_SYSCALL_NUM],
     pub time: usize,
+    pub kernel_clocks: usize,
+    pub user_clocks: usize,
+    pub schedule_count: usize,
+    pub mlfq_level: usize,
+    pub exit_code: i32,
+    pub dispatch_latency: usize,
+    pub tickets: usize,
+    pub voluntary_switches: usize,
+    pub involuntary_switches: usize,
+    pub affinity: usize,
+    pub cpu_clocks: usize,
+    pub cpu_time_ms: usize,
 }
 
 impl TaskInfo {
@@ -127,10 +155,81 @@ impl TaskInfo {
             status: TaskStatus::UnInit,
             syscall_times: [0; MAX_SYSCALL_NUM],
             time: 0,
+            kernel_clocks: 0,
+            user_clocks: 0,
+            schedule_count: 0,
+            mlfq_level: 0,
+            exit_code: 0,
+            dispatch_latency: 0,
+            tickets: 0,
+            voluntary_switches: 0,
+            involuntary_switches: 0,
+            affinity: 0,
+            cpu_clocks: 0,
+            cpu_time_ms: 0,
         }
     }
 }
 
+/// `policy` is 0 for round-robin, then stride, MLFQ, EDF and lottery.
+/// `deadline` is in clocks since boot, 0 for none.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SchedParam {
+    pub policy: usize,
+    pub priority: usize,
+    pub tickets: usize,
+    pub deadline: usize,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TaskBrief {
+    pub id: usize,
+    pub status: TaskStatus,
+    pub cpu_clocks: usize,
+    pub priority: usize,
+}
+
+impl TaskBrief {
+    pub fn new() -> Self {
+        TaskBrief {
+            id: 0,
+            status: TaskStatus::UnInit,
+            cpu_clocks: 0,
+            priority: 0,
+        }
+    }
+}
+
+/// A syscall as the kernel saw it entered, see `sys_dump_trace`. `time` is
+/// in clocks since power-on.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TraceEntry {
+    pub task_id: usize,
+    pub syscall_id: usize,
+    pub arg0: usize,
+    pub time: u64,
+}
+
+#[repr(C)]
+#[derive(Debug, Default)]
+pub struct TrapStats {
+    pub traps: usize,
+    pub timer_interrupts: usize,
+    pub syscalls: usize,
+    pub task_timer_interrupts: usize,
+    pub set_timer_calls: usize,
+    pub set_timer_skipped: usize,
+}
+
+impl TrapStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct Stat {
@@ -236,6 +335,15 @@ pub fn getpid() -> isize {
     sys_getpid()
 }
 
+/// The id of the task running app `name`, if it is loaded.
+pub fn find_task(name: &str) -> Option<usize> {
+    let mut buf = [0u8; 64];
+    (0..sys_get_task_count().max(0) as usize).find(|&tid| {
+        let len = sys_task_name(tid, &mut buf);
+        len >= 0 && &buf[..len as usize] == name.as_bytes()
+    })
+}
+
 pub fn fork() -> isize {
     sys_fork()
 }